@@ -1 +1,14 @@
-
+/// Generates `maybe_*` setters taking an `Option` for optional builder fields,
+/// so callers holding an `Option<T>` do not have to branch before each setter.
+macro_rules! maybe_setters {
+    ($builder:ty { $($field:ident => $setter:ident: $ty:ty),* $(,)? }) => {
+        impl $builder {
+            $(
+                pub fn $setter<VALUE: Into<$ty>>(&mut self, value: Option<VALUE>) -> &mut Self {
+                    self.$field = Some(value.map(Into::into));
+                    self
+                }
+            )*
+        }
+    };
+}
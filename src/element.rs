@@ -1 +1,2 @@
+pub mod content_steering;
 pub mod segment;
@@ -0,0 +1,123 @@
+use derive_builder::Builder;
+#[cfg(feature = "parse")]
+use serde::Deserialize;
+#[cfg(feature = "generate")]
+use serde::Serialize;
+#[cfg(feature = "generate")]
+use serde_with::skip_serializing_none;
+
+use crate::types::XsAnyUri;
+
+/// Attribute name is `ContentSteering`
+#[cfg_attr(feature = "generate", skip_serializing_none)]
+#[derive(Debug, Default, Clone, PartialEq, Eq, Hash, Builder)]
+#[cfg_attr(feature = "generate", derive(Serialize))]
+#[cfg_attr(feature = "parse", derive(Deserialize))]
+#[builder(
+    setter(into, strip_option),
+    default,
+    build_fn(validate = "Self::validate")
+)]
+#[serde(rename = "ContentSteering")]
+pub struct ContentSteering {
+    #[serde(rename = "@defaultServiceLocation")]
+    default_service_location: Option<String>,
+    #[serde(rename = "@queryBeforeStart")]
+    query_before_start: Option<bool>,
+    #[serde(rename = "@clientRequirement")]
+    client_requirement: Option<bool>,
+    /// Steering server URL.
+    #[builder(try_setter)]
+    #[serde(rename = "$text")]
+    url: XsAnyUri,
+}
+
+impl ContentSteeringBuilder {
+    fn validate(&self) -> Result<(), String> {
+        if self.url.as_ref().is_none_or(|url| url.is_empty()) {
+            return Err("ContentSteering requires a steering server URL".to_string());
+        }
+        if let Some(Some(location)) = &self.default_service_location {
+            if location.trim().is_empty() {
+                return Err("@defaultServiceLocation must not be empty".to_string());
+            }
+        }
+        Ok(())
+    }
+}
+
+maybe_setters!(ContentSteeringBuilder {
+    default_service_location => maybe_default_service_location: String,
+    query_before_start => maybe_query_before_start: bool,
+    client_requirement => maybe_client_requirement: bool,
+});
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(all(feature = "parse", feature = "generate"))]
+    #[test]
+    fn test_element_content_steering_serde() {
+        let xml = r#"<ContentSteering defaultServiceLocation="alpha" queryBeforeStart="true">https://steering.example.com/dash.dcsm</ContentSteering>"#;
+
+        let content_steering = ContentSteeringBuilder::default()
+            .default_service_location("alpha")
+            .query_before_start(true)
+            .try_url("https://steering.example.com/dash.dcsm")
+            .unwrap()
+            .build()
+            .unwrap();
+
+        let mut se = String::new();
+        let ser = quick_xml::se::Serializer::new(&mut se);
+        content_steering.serialize(ser).unwrap();
+
+        assert_eq!(xml, se.as_str());
+
+        let der = quick_xml::de::from_str::<ContentSteering>(xml).unwrap();
+
+        assert_eq!(der, content_steering);
+    }
+
+    #[test]
+    fn test_element_content_steering_validate() {
+        assert!(ContentSteeringBuilder::default()
+            .query_before_start(true)
+            .build()
+            .is_err());
+        assert!(ContentSteeringBuilder::default()
+            .default_service_location(" ")
+            .try_url("https://steering.example.com/")
+            .unwrap()
+            .build()
+            .is_err());
+        assert!(ContentSteeringBuilder::default()
+            .try_url("https://steering.example.com/a%zz")
+            .is_err());
+    }
+
+    #[test]
+    fn test_element_content_steering_maybe_setters() {
+        let default_service_location: Option<&str> = None;
+        let content_steering = ContentSteeringBuilder::default()
+            .default_service_location("alpha")
+            .maybe_default_service_location(default_service_location)
+            .maybe_query_before_start(Some(true))
+            .maybe_client_requirement(None::<bool>)
+            .try_url("https://steering.example.com/")
+            .unwrap()
+            .build()
+            .unwrap();
+
+        assert_eq!(
+            content_steering,
+            ContentSteeringBuilder::default()
+                .query_before_start(true)
+                .try_url("https://steering.example.com/")
+                .unwrap()
+                .build()
+                .unwrap()
+        );
+    }
+}
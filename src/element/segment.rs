@@ -1,31 +1,68 @@
 use derive_builder::Builder;
-use serde::{Deserialize, Serialize};
+#[cfg(feature = "parse")]
+use serde::Deserialize;
+#[cfg(feature = "generate")]
+use serde::Serialize;
+#[cfg(feature = "generate")]
 use serde_with::skip_serializing_none;
 
-use crate::types::{FailoverContent, SingleRFC7233RangeType, Url, XsDuration, XsInteger};
+use num::ToPrimitive;
 
-#[skip_serializing_none]
-#[derive(Debug, Default, Clone, Serialize, Deserialize, PartialEq)]
+use crate::error::MpdError;
+#[cfg(feature = "parse")]
+use crate::types::deserialize_opt_from_str;
+use crate::types::{
+    FailoverContent, SingleRFC7233RangeType, Timescale, Url, XsDuration, XsInteger,
+};
+
+#[cfg_attr(feature = "generate", skip_serializing_none)]
+#[derive(Debug, Default, Clone, PartialEq, Builder)]
+#[cfg_attr(feature = "generate", derive(Serialize))]
+#[cfg_attr(feature = "parse", derive(Deserialize))]
+#[builder(setter(into, strip_option), default)]
 pub struct SegmentBaseInformation {
+    #[builder(try_setter)]
     #[serde(rename = "@timescale")]
-    timescale: Option<u32>,
-    #[serde(rename = "@presentationTimeOffset")]
+    timescale: Option<Timescale>,
+    // This struct is always flattened, so its attributes reach the deserializer
+    // as strings; numbers and booleans are parsed from that form.
+    #[serde(
+        rename = "@presentationTimeOffset",
+        default,
+        deserialize_with = "deserialize_opt_from_str"
+    )]
     presentation_time_offset: Option<u64>,
     #[serde(rename = "@eptDelta")]
     ept_delta: Option<XsInteger>,
     #[serde(rename = "@pdDelta")]
     pd_delta: Option<XsInteger>,
-    #[serde(rename = "@presentationDuration")]
+    #[serde(
+        rename = "@presentationDuration",
+        default,
+        deserialize_with = "deserialize_opt_from_str"
+    )]
     presentation_duration: Option<u64>,
     #[serde(rename = "@timeShiftBufferDepth")]
     time_shift_buffer_depth: Option<XsDuration>,
     #[serde(rename = "@indexRange")]
     index_range: Option<SingleRFC7233RangeType>,
-    #[serde(rename = "@indexRangeExact")]
+    #[serde(
+        rename = "@indexRangeExact",
+        default,
+        deserialize_with = "deserialize_opt_from_str"
+    )]
     index_range_exact: Option<bool>,
-    #[serde(rename = "@availabilityTimeOffset")]
+    #[serde(
+        rename = "@availabilityTimeOffset",
+        default,
+        deserialize_with = "deserialize_opt_from_str"
+    )]
     availability_time_offset: Option<f64>,
-    #[serde(rename = "@availabilityTimeComplete")]
+    #[serde(
+        rename = "@availabilityTimeComplete",
+        default,
+        deserialize_with = "deserialize_opt_from_str"
+    )]
     availability_time_complete: Option<bool>,
     #[serde(rename = "Initialization")]
     initialization: Option<Url>,
@@ -35,9 +72,74 @@ pub struct SegmentBaseInformation {
     failover_content: Option<FailoverContent>,
 }
 
-#[skip_serializing_none]
-#[derive(Debug, Default, Clone, Serialize, Deserialize, PartialEq, Builder)]
-#[builder(setter(into, strip_option), default)]
+impl SegmentBaseInformation {
+    /// `@timescale`, defaulting to 1 when absent.
+    pub fn timescale(&self) -> u32 {
+        self.timescale.map_or(1, |timescale| *timescale)
+    }
+}
+
+impl From<SegmentBaseInformation> for SegmentBaseInformationBuilder {
+    fn from(value: SegmentBaseInformation) -> Self {
+        Self {
+            timescale: Some(value.timescale),
+            presentation_time_offset: Some(value.presentation_time_offset),
+            ept_delta: Some(value.ept_delta),
+            pd_delta: Some(value.pd_delta),
+            presentation_duration: Some(value.presentation_duration),
+            time_shift_buffer_depth: Some(value.time_shift_buffer_depth),
+            index_range: Some(value.index_range),
+            index_range_exact: Some(value.index_range_exact),
+            availability_time_offset: Some(value.availability_time_offset),
+            availability_time_complete: Some(value.availability_time_complete),
+            initialization: Some(value.initialization),
+            representation_index: Some(value.representation_index),
+            failover_content: Some(value.failover_content),
+        }
+    }
+}
+
+/// Compares `lhs / lhs_timescale` with `rhs / rhs_timescale` without rounding.
+fn scaled_eq(lhs: u64, lhs_timescale: u32, rhs: u64, rhs_timescale: u32) -> bool {
+    u128::from(lhs) * u128::from(rhs_timescale) == u128::from(rhs) * u128::from(lhs_timescale)
+}
+
+fn scaled_eq_opt(
+    lhs: Option<u64>,
+    lhs_timescale: u32,
+    rhs: Option<u64>,
+    rhs_timescale: u32,
+) -> bool {
+    match (lhs, rhs) {
+        (Some(lhs), Some(rhs)) => scaled_eq(lhs, lhs_timescale, rhs, rhs_timescale),
+        (None, None) => true,
+        _ => false,
+    }
+}
+
+/// Signed counterpart of [`scaled_eq_opt`] for `@eptDelta` and `@pdDelta`.
+fn scaled_eq_signed(
+    lhs: Option<&XsInteger>,
+    lhs_timescale: u32,
+    rhs: Option<&XsInteger>,
+    rhs_timescale: u32,
+) -> bool {
+    match (lhs, rhs) {
+        (Some(lhs), Some(rhs)) => &**lhs * rhs_timescale == &**rhs * lhs_timescale,
+        (None, None) => true,
+        _ => false,
+    }
+}
+
+#[cfg_attr(feature = "generate", skip_serializing_none)]
+#[derive(Debug, Default, Clone, PartialEq, Builder)]
+#[cfg_attr(feature = "generate", derive(Serialize))]
+#[cfg_attr(feature = "parse", derive(Deserialize))]
+#[builder(
+    setter(into, strip_option),
+    default,
+    build_fn(validate = "Self::validate")
+)]
 pub struct MultipleSegmentBaseInformation {
     #[serde(rename = "@duration")]
     duration: Option<u32>,
@@ -53,15 +155,389 @@ pub struct MultipleSegmentBaseInformation {
     bitstream_switching: Option<Url>,
 }
 
-#[derive(Debug, Default, Clone, Serialize, Deserialize, PartialEq, Builder)]
+/// Segment durations of a [`MultipleSegmentBaseInformation`], which carries
+/// either `@duration` or a `SegmentTimeline` but not both.
+#[derive(Debug, Clone, PartialEq)]
+pub enum SegmentDurations {
+    /// `@duration`, in `@timescale` units.
+    Fixed(u32),
+    Timeline(SegmentTimeline),
+}
+
+impl MultipleSegmentBaseInformation {
+    /// `@timescale`, defaulting to 1 when absent. Times taken and returned by
+    /// [`Self::time_for_number`] and [`Self::number_for_time`] are in these
+    /// units.
+    pub fn timescale(&self) -> u32 {
+        self.segment_base_information.timescale()
+    }
+
+    /// Returns true if both describe the same media timing once `@timescale` is
+    /// taken into account, e.g. `@duration="2000"` at timescale 1000 and
+    /// `@duration="180000"` at timescale 90000.
+    ///
+    /// Compares `@duration`, `@presentationTimeOffset`, `@eptDelta`, `@pdDelta`,
+    /// `@presentationDuration`, `@startNumber`, `@endNumber` and the
+    /// `SegmentTimeline` entries.
+    pub fn equivalent_media_timing(&self, other: &Self) -> bool {
+        let lhs = &self.segment_base_information;
+        let rhs = &other.segment_base_information;
+        let (lhs_timescale, rhs_timescale) = (lhs.timescale(), rhs.timescale());
+
+        let timeline_eq = match (&self.segment_timeline, &other.segment_timeline) {
+            (Some(lhs), Some(rhs)) => {
+                lhs.equivalent_media_timing(lhs_timescale, rhs, rhs_timescale)
+            }
+            (None, None) => true,
+            _ => false,
+        };
+
+        timeline_eq
+            && scaled_eq_opt(
+                self.duration.map(u64::from),
+                lhs_timescale,
+                other.duration.map(u64::from),
+                rhs_timescale,
+            )
+            && scaled_eq(
+                lhs.presentation_time_offset.unwrap_or_default(),
+                lhs_timescale,
+                rhs.presentation_time_offset.unwrap_or_default(),
+                rhs_timescale,
+            )
+            && scaled_eq_signed(
+                lhs.ept_delta.as_ref(),
+                lhs_timescale,
+                rhs.ept_delta.as_ref(),
+                rhs_timescale,
+            )
+            && scaled_eq_signed(
+                lhs.pd_delta.as_ref(),
+                lhs_timescale,
+                rhs.pd_delta.as_ref(),
+                rhs_timescale,
+            )
+            && scaled_eq_opt(
+                lhs.presentation_duration,
+                lhs_timescale,
+                rhs.presentation_duration,
+                rhs_timescale,
+            )
+            && self.start_number.unwrap_or(1) == other.start_number.unwrap_or(1)
+            && self.end_number == other.end_number
+    }
+}
+
+/// Run of consecutive segments sharing one duration, in `@timescale` units.
+struct SegmentRun {
+    first_number: u64,
+    start_time: u64,
+    duration: u64,
+    /// `None` when the run repeats until the end of the Period.
+    count: Option<u64>,
+}
+
+impl MultipleSegmentBaseInformation {
+    /// Media time, in `@timescale` units, at which segment `number` starts.
+    ///
+    /// Uses the `SegmentTimeline` when present, otherwise `@duration` offset by
+    /// `@presentationTimeOffset`; having both is an error. `S@k` is not taken
+    /// into account.
+    pub fn time_for_number(&self, number: u64) -> Result<u64, MpdError> {
+        self.check_end_number(number)?;
+        self.segment_runs()?
+            .into_iter()
+            .find(|run| {
+                number >= run.first_number
+                    && run
+                        .count
+                        .is_none_or(|count| number - run.first_number < count)
+            })
+            .and_then(|run| {
+                (number - run.first_number)
+                    .checked_mul(run.duration)?
+                    .checked_add(run.start_time)
+            })
+            .ok_or_else(|| MpdError::OutOfRange {
+                name: "segment number",
+                value: number.to_string(),
+            })
+    }
+
+    /// Number of the segment covering media `time`, in `@timescale` units.
+    ///
+    /// Uses the `SegmentTimeline` when present, otherwise `@duration` offset by
+    /// `@presentationTimeOffset`; having both is an error. `S@k` is not taken
+    /// into account.
+    pub fn number_for_time(&self, time: u64) -> Result<u64, MpdError> {
+        let number = self
+            .segment_runs()?
+            .into_iter()
+            .find(|run| {
+                time >= run.start_time
+                    && run.count.is_none_or(|count| {
+                        u128::from(time - run.start_time)
+                            < u128::from(count) * u128::from(run.duration)
+                    })
+            })
+            .and_then(|run| {
+                run.first_number
+                    .checked_add((time - run.start_time) / run.duration)
+            })
+            .ok_or_else(|| MpdError::OutOfRange {
+                name: "segment time",
+                value: time.to_string(),
+            })?;
+        self.check_end_number(number)?;
+        Ok(number)
+    }
+
+    fn check_end_number(&self, number: u64) -> Result<(), MpdError> {
+        match self.end_number {
+            Some(end_number) if number > u64::from(end_number) => Err(MpdError::OutOfRange {
+                name: "segment number",
+                value: number.to_string(),
+            }),
+            _ => Ok(()),
+        }
+    }
+
+    fn segment_runs(&self) -> Result<Vec<SegmentRun>, MpdError> {
+        let start_number = u64::from(self.start_number.unwrap_or(1));
+
+        // The builder rejects this, but a parsed element may still carry both.
+        if let (Some(duration), Some(_)) = (self.duration, &self.segment_timeline) {
+            return Err(MpdError::InvalidValue {
+                name: "@duration alongside SegmentTimeline",
+                value: duration.to_string(),
+            });
+        }
+
+        let Some(segment_timeline) = &self.segment_timeline else {
+            let duration =
+                self.duration
+                    .filter(|duration| *duration > 0)
+                    .ok_or(MpdError::MissingValue {
+                        name: "@duration or SegmentTimeline",
+                    })?;
+            return Ok(vec![SegmentRun {
+                first_number: start_number,
+                start_time: self
+                    .segment_base_information
+                    .presentation_time_offset
+                    .unwrap_or_default(),
+                duration: duration.into(),
+                count: None,
+            }]);
+        };
+
+        let segments = &segment_timeline.segments;
+        let mut runs = Vec::with_capacity(segments.len());
+        let (mut time, mut number) = (0u64, start_number);
+        for (i, segment) in segments.iter().enumerate() {
+            if segment.duration == 0 {
+                return Err(MpdError::InvalidValue {
+                    name: "S@d",
+                    value: segment.duration.to_string(),
+                });
+            }
+            let invalid_repeat_count = || MpdError::InvalidValue {
+                name: "S@r",
+                value: segment
+                    .repeat_count
+                    .as_ref()
+                    .map(|repeat_count| repeat_count.to_string())
+                    .unwrap_or_default(),
+            };
+            time = segment.start_time.unwrap_or(time);
+            number = segment.number.unwrap_or(number);
+
+            let repeat_count = match &segment.repeat_count {
+                Some(repeat_count) => repeat_count.to_i64().ok_or_else(invalid_repeat_count)?,
+                None => 0,
+            };
+            let count = if repeat_count >= 0 {
+                Some(repeat_count as u64 + 1)
+            } else {
+                // A negative @r repeats until the next S@t, or the end of the Period.
+                match segments.get(i + 1) {
+                    Some(next) => {
+                        let next_time = next.start_time.ok_or_else(invalid_repeat_count)?;
+                        Some(next_time.saturating_sub(time).div_ceil(segment.duration))
+                    }
+                    None => None,
+                }
+            };
+
+            runs.push(SegmentRun {
+                first_number: number,
+                start_time: time,
+                duration: segment.duration,
+                count,
+            });
+            if let Some(count) = count {
+                // Later segments would be numbered or timed past u64::MAX.
+                let Some(next_number) = number.checked_add(count) else {
+                    break;
+                };
+                time = time.saturating_add(count.saturating_mul(segment.duration));
+                number = next_number;
+            }
+        }
+        Ok(runs)
+    }
+}
+
+impl MultipleSegmentBaseInformationBuilder {
+    /// Sets `@duration` or the `SegmentTimeline`, clearing the other.
+    pub fn segment_durations(&mut self, segment_durations: SegmentDurations) -> &mut Self {
+        let (duration, segment_timeline) = match segment_durations {
+            SegmentDurations::Fixed(duration) => (Some(duration), None),
+            SegmentDurations::Timeline(segment_timeline) => (None, Some(segment_timeline)),
+        };
+        self.duration = Some(duration);
+        self.segment_timeline = Some(segment_timeline);
+        self
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        if let (Some(Some(_)), Some(Some(_))) = (&self.duration, &self.segment_timeline) {
+            return Err("@duration and SegmentTimeline are mutually exclusive".to_string());
+        }
+        Ok(())
+    }
+}
+
+maybe_setters!(MultipleSegmentBaseInformationBuilder {
+    duration => maybe_duration: u32,
+    start_number => maybe_start_number: u32,
+    end_number => maybe_end_number: u32,
+    segment_timeline => maybe_segment_timeline: SegmentTimeline,
+    bitstream_switching => maybe_bitstream_switching: Url,
+});
+
+impl From<MultipleSegmentBaseInformation> for MultipleSegmentBaseInformationBuilder {
+    fn from(value: MultipleSegmentBaseInformation) -> Self {
+        Self {
+            duration: Some(value.duration),
+            start_number: Some(value.start_number),
+            end_number: Some(value.end_number),
+            segment_base_information: Some(value.segment_base_information),
+            segment_timeline: Some(value.segment_timeline),
+            bitstream_switching: Some(value.bitstream_switching),
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Builder)]
+#[cfg_attr(feature = "generate", derive(Serialize))]
+#[cfg_attr(feature = "parse", derive(Deserialize))]
 #[builder(setter(into, strip_option), default)]
 pub struct SegmentBase {
     #[serde(flatten)]
     segment_base_information: SegmentBaseInformation,
 }
 
+/// Subsegment reference of a `sidx` box, as read by the caller.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct SubsegmentReference {
+    /// Size in bytes of the referenced subsegment.
+    pub referenced_size: u32,
+    /// Duration in `sidx` timescale units.
+    pub subsegment_duration: u32,
+}
+
+/// Fields of a `sidx` box needed to locate its subsegments.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Hash)]
+pub struct SegmentIndex {
+    pub timescale: u32,
+    pub earliest_presentation_time: u64,
+    /// Distance in bytes from the end of the `sidx` box to the first subsegment.
+    pub first_offset: u64,
+    pub references: Vec<SubsegmentReference>,
+}
+
+impl SegmentIndex {
+    /// Earliest presentation time, in `sidx` timescale units, of the subsegment
+    /// at `subsegment_index`. Returns `None` if the index is out of range or the
+    /// time overflows.
+    pub fn presentation_time_for(&self, subsegment_index: usize) -> Option<u64> {
+        self.references.get(subsegment_index)?;
+        self.references[..subsegment_index]
+            .iter()
+            .try_fold(self.earliest_presentation_time, |time, reference| {
+                time.checked_add(reference.subsegment_duration.into())
+            })
+    }
+}
+
+impl SegmentBase {
+    /// `@timescale`, defaulting to 1 when absent.
+    pub fn timescale(&self) -> u32 {
+        self.segment_base_information.timescale()
+    }
+
+    /// Byte range of the subsegment at `subsegment_index`, using `@indexRange`
+    /// to locate the end of the `sidx` box described by `index`.
+    pub fn byte_range_for(
+        &self,
+        index: &SegmentIndex,
+        subsegment_index: usize,
+    ) -> Result<SingleRFC7233RangeType, MpdError> {
+        let index_end = self
+            .segment_base_information
+            .index_range
+            .as_ref()
+            .and_then(|index_range| index_range.end)
+            .ok_or(MpdError::MissingValue {
+                name: "@indexRange",
+            })?;
+        let out_of_range = || MpdError::OutOfRange {
+            name: "subsegment index",
+            value: subsegment_index.to_string(),
+        };
+        let reference = index
+            .references
+            .get(subsegment_index)
+            .ok_or_else(out_of_range)?;
+        if reference.referenced_size == 0 {
+            return Err(MpdError::InvalidValue {
+                name: "referenced_size",
+                value: reference.referenced_size.to_string(),
+            });
+        }
+
+        let range = index.references[..subsegment_index]
+            .iter()
+            .try_fold(index_end, |offset, reference| {
+                offset.checked_add(reference.referenced_size.into())
+            })
+            .and_then(|offset| offset.checked_add(1)?.checked_add(index.first_offset))
+            .and_then(|start| {
+                let end = start.checked_add(u64::from(reference.referenced_size) - 1)?;
+                Some((Some(start), Some(end)))
+            })
+            .ok_or_else(|| MpdError::OutOfRange {
+                name: "subsegment byte range",
+                value: subsegment_index.to_string(),
+            })?;
+
+        Ok(SingleRFC7233RangeType::from(range))
+    }
+}
+
+impl From<SegmentBase> for SegmentBaseBuilder {
+    fn from(value: SegmentBase) -> Self {
+        Self {
+            segment_base_information: Some(value.segment_base_information),
+        }
+    }
+}
+
 /// Attribute name is `SegmentTimeline`
-#[derive(Debug, Default, Clone, Serialize, Deserialize, PartialEq, Eq, Hash, Builder)]
+#[derive(Debug, Default, Clone, PartialEq, Eq, Hash, Builder)]
+#[cfg_attr(feature = "generate", derive(Serialize))]
+#[cfg_attr(feature = "parse", derive(Deserialize))]
 #[builder(setter(into, strip_option), default)]
 pub struct SegmentTimeline {
     #[builder(setter(custom))]
@@ -84,9 +560,32 @@ impl SegmentTimelineBuilder {
     }
 }
 
+impl SegmentTimeline {
+    fn equivalent_media_timing(&self, timescale: u32, other: &Self, other_timescale: u32) -> bool {
+        self.segments.len() == other.segments.len()
+            && self.segments.iter().zip(&other.segments).all(|(lhs, rhs)| {
+                scaled_eq_opt(lhs.start_time, timescale, rhs.start_time, other_timescale)
+                    && scaled_eq(lhs.duration, timescale, rhs.duration, other_timescale)
+                    && lhs.number == rhs.number
+                    && lhs.segment_count == rhs.segment_count
+                    && lhs.repeat_count == rhs.repeat_count
+            })
+    }
+}
+
+impl From<SegmentTimeline> for SegmentTimelineBuilder {
+    fn from(value: SegmentTimeline) -> Self {
+        Self {
+            segments: Some(value.segments),
+        }
+    }
+}
+
 /// Attribute name is `S`
-#[skip_serializing_none]
-#[derive(Debug, Default, Clone, Serialize, Deserialize, PartialEq, Eq, Hash, Builder)]
+#[cfg_attr(feature = "generate", skip_serializing_none)]
+#[derive(Debug, Default, Clone, PartialEq, Eq, Hash, Builder)]
+#[cfg_attr(feature = "generate", derive(Serialize))]
+#[cfg_attr(feature = "parse", derive(Deserialize))]
 #[builder(setter(into, strip_option), default)]
 #[serde(rename = "S")]
 pub struct Segment {
@@ -102,10 +601,30 @@ pub struct Segment {
     repeat_count: Option<XsInteger>,
 }
 
+maybe_setters!(SegmentBuilder {
+    start_time => maybe_start_time: u64,
+    number => maybe_number: u64,
+    segment_count => maybe_segment_count: u64,
+    repeat_count => maybe_repeat_count: XsInteger,
+});
+
+impl From<Segment> for SegmentBuilder {
+    fn from(value: Segment) -> Self {
+        Self {
+            start_time: Some(value.start_time),
+            number: Some(value.number),
+            duration: Some(value.duration),
+            segment_count: Some(value.segment_count),
+            repeat_count: Some(value.repeat_count),
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[cfg(feature = "generate")]
     #[test]
     fn test_element_segment_timeline() {
         let segment1 = SegmentBuilder::default()
@@ -142,20 +661,475 @@ mod tests {
   <S d="5" r="15"/>
 </SegmentTimeline>"#;
 
-        assert!(&xml == se);
+        assert!(xml == se);
+    }
+
+    #[cfg(all(feature = "parse", feature = "generate"))]
+    #[test]
+    fn test_element_segment_timeline_into_builder() {
+        let xml = r#"<SegmentTimeline>
+  <S t="0" d="5" r="10"/>
+</SegmentTimeline>"#;
+        let segment_timeline = quick_xml::de::from_str::<SegmentTimeline>(xml).unwrap();
+
+        let segment = SegmentBuilder::from(segment_timeline.segments[0].clone())
+            .start_time(55u64)
+            .repeat_count(0)
+            .build()
+            .unwrap();
+        let segment_timeline = SegmentTimelineBuilder::from(segment_timeline)
+            .segment(segment)
+            .build()
+            .unwrap();
+
+        let mut xml = String::new();
+        let mut ser = quick_xml::se::Serializer::new(&mut xml);
+        ser.indent(' ', 2);
+        segment_timeline.serialize(ser).unwrap();
+
+        let se = r#"<SegmentTimeline>
+  <S t="0" d="5" r="10"/>
+  <S t="55" d="5" r="0"/>
+</SegmentTimeline>"#;
+
+        assert!(xml == se);
+    }
+
+    #[cfg(all(feature = "parse", feature = "generate"))]
+    #[test]
+    fn test_element_multiple_segment_base_into_builder() {
+        let xml = r#"<MultipleSegmentBaseInformation duration="2000" startNumber="5" timescale="1000" presentationTimeOffset="100"><BitstreamSwitching sourceURL="bs.mp4"/></MultipleSegmentBaseInformation>"#;
+        let multiple_segment_base =
+            quick_xml::de::from_str::<MultipleSegmentBaseInformation>(xml).unwrap();
+
+        assert_eq!(multiple_segment_base.timescale(), 1000);
+
+        assert_eq!(
+            MultipleSegmentBaseInformationBuilder::from(multiple_segment_base.clone())
+                .build()
+                .unwrap(),
+            multiple_segment_base
+        );
+
+        let multiple_segment_base =
+            MultipleSegmentBaseInformationBuilder::from(multiple_segment_base)
+                .end_number(9u32)
+                .build()
+                .unwrap();
+
+        let mut se = String::new();
+        let ser =
+            quick_xml::se::Serializer::with_root(&mut se, Some("MultipleSegmentBaseInformation"))
+                .unwrap();
+        multiple_segment_base.serialize(ser).unwrap();
+
+        assert_eq!(
+            se,
+            r#"<MultipleSegmentBaseInformation duration="2000" startNumber="5" endNumber="9" timescale="1000" presentationTimeOffset="100"><BitstreamSwitching sourceURL="bs.mp4"/></MultipleSegmentBaseInformation>"#
+        );
+    }
+
+    #[cfg(all(feature = "parse", feature = "generate"))]
+    #[test]
+    fn test_element_segment_base_into_builder() {
+        let xml = r#"<SegmentBase timescale="90000" presentationTimeOffset="100" presentationDuration="900000" indexRange="800-899" indexRangeExact="true" availabilityTimeOffset="1.5" availabilityTimeComplete="false"><Initialization range="0-799"/></SegmentBase>"#;
+        let segment_base = quick_xml::de::from_str::<SegmentBase>(xml).unwrap();
+
+        assert_eq!(segment_base.timescale(), 90000);
+        assert_eq!(
+            segment_base
+                .segment_base_information
+                .presentation_time_offset,
+            Some(100)
+        );
+        assert_eq!(
+            segment_base.segment_base_information.index_range_exact,
+            Some(true)
+        );
+        assert_eq!(
+            SegmentBaseBuilder::from(segment_base.clone())
+                .build()
+                .unwrap(),
+            segment_base
+        );
+
+        let mut se = String::new();
+        let ser = quick_xml::se::Serializer::with_root(&mut se, Some("SegmentBase")).unwrap();
+        SegmentBaseBuilder::from(segment_base)
+            .build()
+            .unwrap()
+            .serialize(ser)
+            .unwrap();
+
+        assert_eq!(se, xml);
+    }
+
+    #[test]
+    fn test_element_segment_maybe_setters() {
+        let start_time: Option<u64> = None;
+        let segment = SegmentBuilder::default()
+            .duration(5u64)
+            .start_time(10u64)
+            .maybe_start_time(start_time)
+            .maybe_repeat_count(Some(3))
+            .build()
+            .unwrap();
+
+        assert_eq!(
+            segment,
+            SegmentBuilder::default()
+                .duration(5u64)
+                .repeat_count(3)
+                .build()
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn test_element_multiple_segment_base_equivalent_media_timing() {
+        let multiple_segment_base = |timescale: u32, segment_durations: SegmentDurations| {
+            MultipleSegmentBaseInformationBuilder::default()
+                .segment_base_information(
+                    SegmentBaseInformationBuilder::default()
+                        .try_timescale(timescale)
+                        .unwrap()
+                        .presentation_time_offset(u64::from(timescale) * 10)
+                        .build()
+                        .unwrap(),
+                )
+                .segment_durations(segment_durations)
+                .build()
+                .unwrap()
+        };
+        let timeline = |d: u64| {
+            SegmentDurations::Timeline(
+                SegmentTimelineBuilder::default()
+                    .segment(
+                        SegmentBuilder::default()
+                            .start_time(0u64)
+                            .duration(d)
+                            .repeat_count(4)
+                            .build()
+                            .unwrap(),
+                    )
+                    .build()
+                    .unwrap(),
+            )
+        };
+
+        let lhs = multiple_segment_base(1000, SegmentDurations::Fixed(2000));
+        let rhs = multiple_segment_base(90000, SegmentDurations::Fixed(180000));
+
+        assert!(lhs != rhs);
+        assert!(lhs.equivalent_media_timing(&rhs));
+        assert!(!lhs.equivalent_media_timing(&multiple_segment_base(
+            90000,
+            SegmentDurations::Fixed(90000)
+        )));
+        assert!(!lhs.equivalent_media_timing(&multiple_segment_base(90000, timeline(180000))));
+
+        let lhs = multiple_segment_base(1000, timeline(2000));
+
+        assert!(lhs.equivalent_media_timing(&multiple_segment_base(90000, timeline(180000))));
+        assert!(!lhs.equivalent_media_timing(&multiple_segment_base(90000, timeline(90000))));
+
+        let with_deltas = |timescale: u32, ept_delta: i64, pd_delta: i64| {
+            MultipleSegmentBaseInformationBuilder::default()
+                .segment_base_information(
+                    SegmentBaseInformationBuilder::default()
+                        .try_timescale(timescale)
+                        .unwrap()
+                        .ept_delta(ept_delta)
+                        .pd_delta(pd_delta)
+                        .build()
+                        .unwrap(),
+                )
+                .duration(timescale)
+                .build()
+                .unwrap()
+        };
+        let lhs = with_deltas(1000, -500, 250);
+
+        assert!(lhs.equivalent_media_timing(&with_deltas(90000, -45000, 22500)));
+        assert!(!lhs.equivalent_media_timing(&with_deltas(90000, 45000, 22500)));
+        assert!(!lhs.equivalent_media_timing(&with_deltas(90000, -45000, 250)));
+
+        let mut without_ept_delta = lhs.clone();
+        without_ept_delta.segment_base_information.ept_delta = None;
+
+        assert!(!lhs.equivalent_media_timing(&without_ept_delta));
+    }
+
+    #[test]
+    fn test_element_multiple_segment_base_segment_durations() {
+        let segment_timeline = SegmentTimelineBuilder::default().build().unwrap();
+
+        assert!(MultipleSegmentBaseInformationBuilder::default()
+            .duration(2000u32)
+            .segment_timeline(segment_timeline.clone())
+            .build()
+            .is_err());
+
+        let multiple_segment_base = MultipleSegmentBaseInformationBuilder::default()
+            .duration(2000u32)
+            .segment_durations(SegmentDurations::Timeline(segment_timeline.clone()))
+            .build()
+            .unwrap();
+
+        assert_eq!(multiple_segment_base.duration, None);
+        assert_eq!(
+            multiple_segment_base.segment_timeline,
+            Some(segment_timeline)
+        );
+    }
+
+    #[cfg(feature = "parse")]
+    #[test]
+    fn test_element_multiple_segment_base_de_duration_and_timeline() {
+        let xml = r#"<MultipleSegmentBaseInformation duration="2"><SegmentTimeline><S t="0" d="5"/></SegmentTimeline></MultipleSegmentBaseInformation>"#;
+        let multiple_segment_base =
+            quick_xml::de::from_str::<MultipleSegmentBaseInformation>(xml).unwrap();
+
+        assert_eq!(
+            multiple_segment_base.time_for_number(2),
+            Err(MpdError::InvalidValue {
+                name: "@duration alongside SegmentTimeline",
+                value: "2".to_string()
+            })
+        );
+        assert!(multiple_segment_base.number_for_time(0).is_err());
+    }
+
+    #[test]
+    fn test_element_multiple_segment_base_number_time_duration() {
+        let multiple_segment_base = MultipleSegmentBaseInformationBuilder::default()
+            .duration(2000u32)
+            .start_number(5u32)
+            .end_number(9u32)
+            .segment_base_information(
+                SegmentBaseInformationBuilder::default()
+                    .presentation_time_offset(1000u64)
+                    .build()
+                    .unwrap(),
+            )
+            .build()
+            .unwrap();
+
+        assert_eq!(multiple_segment_base.timescale(), 1);
+        assert_eq!(multiple_segment_base.time_for_number(5), Ok(1000));
+        assert_eq!(multiple_segment_base.time_for_number(7), Ok(5000));
+        assert_eq!(multiple_segment_base.number_for_time(5999), Ok(7));
+        assert!(multiple_segment_base.time_for_number(4).is_err());
+        assert!(multiple_segment_base.time_for_number(10).is_err());
+        assert!(multiple_segment_base.number_for_time(999).is_err());
+        assert!(multiple_segment_base.number_for_time(11000).is_err());
+
+        assert_eq!(
+            MultipleSegmentBaseInformation::default().time_for_number(1),
+            Err(MpdError::MissingValue {
+                name: "@duration or SegmentTimeline"
+            })
+        );
+    }
+
+    #[test]
+    fn test_element_multiple_segment_base_number_time_timeline() {
+        let segment = |start_time: Option<u64>, duration: u64, repeat_count: Option<i64>| {
+            SegmentBuilder::default()
+                .maybe_start_time(start_time)
+                .duration(duration)
+                .maybe_repeat_count(repeat_count)
+                .build()
+                .unwrap()
+        };
+        // <S t="100" d="10" r="2"/><S d="20"/><S t="200" d="5" r="-1"/><S t="300" d="50" r="-1"/>
+        let multiple_segment_base = MultipleSegmentBaseInformationBuilder::default()
+            .segment_timeline(
+                SegmentTimelineBuilder::default()
+                    .segments([
+                        segment(Some(100), 10, Some(2)),
+                        segment(None, 20, None),
+                        segment(Some(200), 5, Some(-1)),
+                        segment(Some(300), 50, Some(-1)),
+                    ])
+                    .build()
+                    .unwrap(),
+            )
+            .build()
+            .unwrap();
+
+        for (number, time) in [
+            (1, 100),
+            (3, 120),
+            (4, 130),
+            (5, 200),
+            (24, 295),
+            (25, 300),
+            (27, 400),
+        ] {
+            assert_eq!(multiple_segment_base.time_for_number(number), Ok(time));
+            assert_eq!(multiple_segment_base.number_for_time(time), Ok(number));
+        }
+        assert_eq!(multiple_segment_base.number_for_time(149), Ok(4));
+        assert!(multiple_segment_base.number_for_time(150).is_err());
+        assert!(multiple_segment_base.number_for_time(99).is_err());
+        assert!(multiple_segment_base.time_for_number(0).is_err());
+
+        let timeline = |segments: [Segment; 2]| {
+            MultipleSegmentBaseInformationBuilder::default()
+                .segment_timeline(
+                    SegmentTimelineBuilder::default()
+                        .segments(segments)
+                        .build()
+                        .unwrap(),
+                )
+                .build()
+                .unwrap()
+        };
+        assert_eq!(
+            timeline([segment(Some(0), 10, None), segment(None, 0, None)]).time_for_number(1),
+            Err(MpdError::InvalidValue {
+                name: "S@d",
+                value: "0".to_string()
+            })
+        );
+        assert_eq!(
+            timeline([segment(Some(0), 10, Some(-1)), segment(None, 5, None)]).time_for_number(1),
+            Err(MpdError::InvalidValue {
+                name: "S@r",
+                value: "-1".to_string()
+            })
+        );
+    }
+
+    #[test]
+    fn test_element_multiple_segment_base_number_time_overflow() {
+        let multiple_segment_base = MultipleSegmentBaseInformationBuilder::default()
+            .duration(1u32)
+            .start_number(2u32)
+            .build()
+            .unwrap();
+
+        assert_eq!(
+            multiple_segment_base.number_for_time(u64::MAX),
+            Err(MpdError::OutOfRange {
+                name: "segment time",
+                value: u64::MAX.to_string()
+            })
+        );
+
+        let multiple_segment_base = MultipleSegmentBaseInformationBuilder::default()
+            .segment_timeline(
+                SegmentTimelineBuilder::default()
+                    .segment(
+                        SegmentBuilder::default()
+                            .start_time(0u64)
+                            .number(u64::MAX - 1)
+                            .duration(1u64)
+                            .repeat_count(-1)
+                            .build()
+                            .unwrap(),
+                    )
+                    .build()
+                    .unwrap(),
+            )
+            .build()
+            .unwrap();
+
+        assert_eq!(multiple_segment_base.number_for_time(1), Ok(u64::MAX));
+        assert!(multiple_segment_base.number_for_time(2).is_err());
+        assert_eq!(multiple_segment_base.time_for_number(u64::MAX), Ok(1));
+    }
+
+    #[test]
+    fn test_element_segment_base_byte_range_for() {
+        let segment_base = SegmentBaseBuilder::default()
+            .segment_base_information(
+                SegmentBaseInformationBuilder::default()
+                    .index_range((Some(800), Some(899)))
+                    .initialization(Url::from((None, Some((Some(0), Some(799)).into()))))
+                    .build()
+                    .unwrap(),
+            )
+            .build()
+            .unwrap();
+        let index = SegmentIndex {
+            timescale: 1000,
+            earliest_presentation_time: 40,
+            first_offset: 100,
+            references: vec![
+                SubsegmentReference {
+                    referenced_size: 5000,
+                    subsegment_duration: 2000,
+                },
+                SubsegmentReference {
+                    referenced_size: 4000,
+                    subsegment_duration: 1990,
+                },
+            ],
+        };
+
+        assert_eq!(
+            segment_base.byte_range_for(&index, 0),
+            Ok("1000-5999".parse().unwrap())
+        );
+        assert_eq!(
+            segment_base.byte_range_for(&index, 1),
+            Ok("6000-9999".parse().unwrap())
+        );
+        assert!(segment_base.byte_range_for(&index, 2).is_err());
+        assert_eq!(
+            segment_base.byte_range_for(
+                &SegmentIndex {
+                    first_offset: u64::MAX,
+                    ..index.clone()
+                },
+                0
+            ),
+            Err(MpdError::OutOfRange {
+                name: "subsegment byte range",
+                value: "0".to_string()
+            })
+        );
+        let mut zero_size = index.clone();
+        zero_size.references[1].referenced_size = 0;
+        assert_eq!(
+            segment_base.byte_range_for(&zero_size, 1),
+            Err(MpdError::InvalidValue {
+                name: "referenced_size",
+                value: "0".to_string()
+            })
+        );
+        assert_eq!(index.presentation_time_for(1), Some(2040));
+        assert_eq!(index.presentation_time_for(2), None);
+        assert_eq!(
+            SegmentIndex {
+                earliest_presentation_time: u64::MAX,
+                ..index.clone()
+            }
+            .presentation_time_for(1),
+            None
+        );
+
+        assert_eq!(
+            SegmentBase::default().byte_range_for(&index, 0),
+            Err(MpdError::MissingValue {
+                name: "@indexRange"
+            })
+        );
     }
 
+    #[cfg(feature = "generate")]
     #[test]
     fn test_element_segment_base() {
         let base = SegmentBaseInformation::default();
-        println!("{}", serde_plain::to_string(&base).unwrap());
 
         let mut xml = String::new();
         let mut ser = quick_xml::se::Serializer::new(&mut xml);
         ser.indent(' ', 2);
         base.serialize(ser).unwrap();
 
-        println!("{xml}");
+        assert_eq!(xml, "<SegmentBaseInformation/>");
     }
 }
 
@@ -0,0 +1,27 @@
+use std::fmt;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MpdError {
+    /// The value could not be parsed into the target type.
+    InvalidValue { name: &'static str, value: String },
+    /// The value was parsed but lies outside the range allowed for the type.
+    OutOfRange { name: &'static str, value: String },
+    /// A value required for the operation is absent.
+    MissingValue { name: &'static str },
+}
+
+impl fmt::Display for MpdError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MpdError::InvalidValue { name, value } => {
+                write!(f, "invalid value for {}: {:?}", name, value)
+            }
+            MpdError::OutOfRange { name, value } => {
+                write!(f, "value out of range for {}: {:?}", name, value)
+            }
+            MpdError::MissingValue { name } => write!(f, "missing value for {}", name),
+        }
+    }
+}
+
+impl std::error::Error for MpdError {}
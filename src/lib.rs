@@ -1,5 +1,53 @@
+#[cfg(not(any(feature = "parse", feature = "generate")))]
+compile_error!("at least one of the `parse` or `generate` features must be enabled");
+
+#[macro_use]
 mod common;
 mod element;
+mod error;
 mod types;
 
-pub use element::segment::{Segment, SegmentBuilder, SegmentTimeline, SegmentTimelineBuilder};
+pub use element::content_steering::{ContentSteering, ContentSteeringBuilder};
+pub use element::segment::{
+    MultipleSegmentBaseInformation, MultipleSegmentBaseInformationBuilder, Segment, SegmentBase,
+    SegmentBaseBuilder, SegmentBaseInformation, SegmentBaseInformationBuilder, SegmentBuilder,
+    SegmentDurations, SegmentIndex, SegmentTimeline, SegmentTimelineBuilder, SubsegmentReference,
+};
+pub use error::MpdError;
+pub use types::{
+    Bandwidth, FailoverContent, FailoverContentBuilder, Fcs, Percentage, SingleRFC7233RangeType,
+    Timescale, Url, XsAnyUri, XsDuration,
+};
+
+/// Checked at compile time so that a non-`Send` or non-`Sync` field breaks the build.
+const _: () = {
+    const fn assert_send_sync<T: Send + Sync>() {}
+
+    assert_send_sync::<ContentSteering>();
+    assert_send_sync::<ContentSteeringBuilder>();
+    assert_send_sync::<MultipleSegmentBaseInformation>();
+    assert_send_sync::<MultipleSegmentBaseInformationBuilder>();
+    assert_send_sync::<Segment>();
+    assert_send_sync::<SegmentBase>();
+    assert_send_sync::<SegmentBaseBuilder>();
+    assert_send_sync::<SegmentBaseInformation>();
+    assert_send_sync::<SegmentBaseInformationBuilder>();
+    assert_send_sync::<SegmentBuilder>();
+    assert_send_sync::<SegmentDurations>();
+    assert_send_sync::<SegmentIndex>();
+    assert_send_sync::<SegmentTimeline>();
+    assert_send_sync::<SegmentTimelineBuilder>();
+    assert_send_sync::<SubsegmentReference>();
+    assert_send_sync::<MpdError>();
+    assert_send_sync::<Bandwidth>();
+    assert_send_sync::<Percentage>();
+    assert_send_sync::<Timescale>();
+    assert_send_sync::<XsDuration>();
+    assert_send_sync::<types::XsInteger>();
+    assert_send_sync::<SingleRFC7233RangeType>();
+    assert_send_sync::<XsAnyUri>();
+    assert_send_sync::<Url>();
+    assert_send_sync::<Fcs>();
+    assert_send_sync::<FailoverContent>();
+    assert_send_sync::<FailoverContentBuilder>();
+};
@@ -1,27 +1,466 @@
-use std::ops::Deref;
-
+use std::cmp::Ordering;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::ops::{Add, Deref, Mul, Neg, Range, RangeInclusive, Sub};
+use std::str::FromStr;
+use std::sync::LazyLock;
+
+use derive_builder::Builder;
 use num::BigInt;
 use regex::Regex;
-use serde::{Deserialize, Deserializer, Serialize, Serializer};
+#[cfg(feature = "parse")]
+use serde::{Deserialize, Deserializer};
+#[cfg(feature = "generate")]
+use serde::{Serialize, Serializer};
+
+use crate::error::MpdError;
+
+static PATTERN_XS_DURATION: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r"^(-)?P(?:([0-9]+)Y)?(?:([0-9]+)M)?(?:([0-9]+)D)?(?:T(?:([0-9]+)H)?(?:([0-9]+)M)?(?:([0-9]+)(?:\.([0-9]+))?S)?)?$",
+    )
+    .unwrap()
+});
+
+/// RFC 3986 Appendix B.
+static PATTERN_URI_REFERENCE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^(([^:/?#]+):)?(//([^/?#]*))?([^?#]*)(\?([^#]*))?(#(.*))?$").unwrap()
+});
+
+static PATTERN_URI_SCHEME: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^[A-Za-z][A-Za-z0-9+.-]*$").unwrap());
+
+static PATTERN_SINGLE_RFC7233_RANGE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^([0-9]*)(-([0-9]*))?$").unwrap());
+
+/// Deserializes an optional value from its string form with [`FromStr`].
+#[cfg(feature = "parse")]
+pub(crate) fn deserialize_opt_from_str<'de, D, T>(deserializer: D) -> Result<Option<T>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: FromStr,
+    T::Err: fmt::Display,
+{
+    Option::<String>::deserialize(deserializer)?
+        .map(|s| s.parse().map_err(serde::de::Error::custom))
+        .transpose()
+}
 
-#[derive(Debug, Default, Clone, PartialEq, Eq)]
-pub struct XsDuration(iso8601::Duration);
+/// `xs:duration` keeping the calendar components as written.
+///
+/// Years and months are not folded into seconds, so `P1M` stays one month on
+/// round trip. Fractional seconds are kept up to nanosecond precision.
+///
+/// Equality, ordering and arithmetic follow the `xs:duration` value space of
+/// (months, seconds): `PT60S == PT1M`, but `P1M` and `P30D` are unordered.
+/// Arithmetic results are normalized, e.g. `PT90M + PT0S` is `PT1H30M`.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct XsDuration {
+    pub negative: bool,
+    pub years: u64,
+    pub months: u64,
+    pub days: u64,
+    pub hours: u64,
+    pub minutes: u64,
+    pub seconds: u64,
+    /// Fractional part of `seconds`. Values of `1_000_000_000` or more carry
+    /// into `seconds` when formatted or compared.
+    pub nanoseconds: u32,
+}
 
-impl Deref for XsDuration {
-    type Target = iso8601::Duration;
+impl XsDuration {
+    const SECS_PER_DAY: u64 = 86_400;
+    /// Days per year used by the approximate conversions.
+    const DAYS_PER_YEAR: u64 = 365;
+    /// Days per month used by the approximate conversions.
+    const DAYS_PER_MONTH: u64 = 30;
+
+    pub fn is_zero(&self) -> bool {
+        self.years == 0
+            && self.months == 0
+            && self.days == 0
+            && self.hours == 0
+            && self.minutes == 0
+            && self.seconds == 0
+            && self.nanoseconds == 0
+    }
 
-    fn deref(&self) -> &Self::Target {
-        &self.0
+    /// Whole seconds of the absolute value, counting a year as 365 days and a
+    /// month as 30 days. Returns `None` on overflow.
+    fn total_secs_approx(&self) -> Option<u64> {
+        let days = self
+            .years
+            .checked_mul(Self::DAYS_PER_YEAR)?
+            .checked_add(self.months.checked_mul(Self::DAYS_PER_MONTH)?)?
+            .checked_add(self.days)?;
+        days.checked_mul(Self::SECS_PER_DAY)?
+            .checked_add(self.hours.checked_mul(3600)?)?
+            .checked_add(self.minutes.checked_mul(60)?)?
+            .checked_add(self.seconds)?
+            .checked_add(u64::from(self.nanoseconds / 1_000_000_000))
+    }
+
+    /// Signed (months, nanoseconds) pair of the `xs:duration` value space.
+    fn months_and_nanos(&self) -> (i128, i128) {
+        let sign = if self.negative { -1 } else { 1 };
+        let months = i128::from(self.years) * 12 + i128::from(self.months);
+        let secs = i128::from(self.days) * i128::from(Self::SECS_PER_DAY)
+            + i128::from(self.hours) * 3600
+            + i128::from(self.minutes) * 60
+            + i128::from(self.seconds);
+        let nanos = secs * 1_000_000_000 + i128::from(self.nanoseconds);
+        (sign * months, sign * nanos)
+    }
+
+    /// Builds a normalized duration from the value space. Returns `None` when
+    /// months and nanoseconds have opposite signs, which `xs:duration` cannot
+    /// express, or when a component overflows.
+    fn from_months_and_nanos(months: i128, nanos: i128) -> Option<Self> {
+        if (months < 0 && nanos > 0) || (months > 0 && nanos < 0) {
+            return None;
+        }
+        let negative = months < 0 || nanos < 0;
+        let (months, nanos) = (months.unsigned_abs(), nanos.unsigned_abs());
+        let secs = nanos / 1_000_000_000;
+        let secs_per_day = u128::from(Self::SECS_PER_DAY);
+        Some(Self {
+            negative,
+            years: u64::try_from(months / 12).ok()?,
+            months: (months % 12) as u64,
+            days: u64::try_from(secs / secs_per_day).ok()?,
+            hours: (secs % secs_per_day / 3600) as u64,
+            minutes: (secs % 3600 / 60) as u64,
+            seconds: (secs % 60) as u64,
+            nanoseconds: (nanos % 1_000_000_000) as u32,
+        })
+    }
+
+    /// Like [`Self::from_months_and_nanos`], but never fails: components that
+    /// overflow are clamped to their maximum, and a seconds part whose sign
+    /// disagrees with a non-zero months part is clamped to zero.
+    fn from_months_and_nanos_saturating(months: i128, nanos: i128) -> Self {
+        const MAX_MONTHS: i128 = u64::MAX as i128 * 12 + 11;
+        const MAX_NANOS: i128 = (u64::MAX as i128 + 1) * 86_400 * 1_000_000_000 - 1;
+        let months = months.clamp(-MAX_MONTHS, MAX_MONTHS);
+        let nanos = match months.signum() {
+            1 => nanos.clamp(0, MAX_NANOS),
+            -1 => nanos.clamp(-MAX_NANOS, 0),
+            _ => nanos.clamp(-MAX_NANOS, MAX_NANOS),
+        };
+        Self::from_months_and_nanos(months, nanos).expect("clamped to a representable duration")
+    }
+
+    /// Returns `None` if the sum cannot be expressed as an `xs:duration`.
+    pub fn checked_add(self, rhs: Self) -> Option<Self> {
+        let (lhs_months, lhs_nanos) = self.months_and_nanos();
+        let (rhs_months, rhs_nanos) = rhs.months_and_nanos();
+        Self::from_months_and_nanos(
+            lhs_months.checked_add(rhs_months)?,
+            lhs_nanos.checked_add(rhs_nanos)?,
+        )
+    }
+
+    /// Returns `None` if the difference cannot be expressed as an `xs:duration`.
+    pub fn checked_sub(self, rhs: Self) -> Option<Self> {
+        self.checked_add(-rhs)
+    }
+
+    /// Sum that never fails, e.g. `P1M + -P1D` is `P1M`.
+    ///
+    /// When the months and seconds parts end up with opposite signs, the seconds
+    /// part is clamped to zero. Components that overflow are clamped to their
+    /// maximum.
+    pub fn saturating_add(self, rhs: Self) -> Self {
+        let (lhs_months, lhs_nanos) = self.months_and_nanos();
+        let (rhs_months, rhs_nanos) = rhs.months_and_nanos();
+        Self::from_months_and_nanos_saturating(
+            lhs_months.saturating_add(rhs_months),
+            lhs_nanos.saturating_add(rhs_nanos),
+        )
+    }
+
+    /// Difference that never fails, clamped like [`Self::saturating_add`].
+    pub fn saturating_sub(self, rhs: Self) -> Self {
+        self.saturating_add(-rhs)
+    }
+
+    /// Returns `None` if the product overflows.
+    pub fn checked_mul(self, rhs: u32) -> Option<Self> {
+        let (months, nanos) = self.months_and_nanos();
+        Self::from_months_and_nanos(
+            months.checked_mul(rhs.into())?,
+            nanos.checked_mul(rhs.into())?,
+        )
+    }
+
+    /// Converts to [`std::time::Duration`], counting a year as 365 days and a
+    /// month as 30 days. Returns `None` for negative durations or on overflow.
+    pub fn to_std_duration_approx(self) -> Option<std::time::Duration> {
+        if self.negative && !self.is_zero() {
+            return None;
+        }
+        let secs = self.total_secs_approx()?;
+        std::time::Duration::from_secs(secs).checked_add(std::time::Duration::from_nanos(
+            (self.nanoseconds % 1_000_000_000).into(),
+        ))
+    }
+
+    /// Converts to [`chrono::Duration`], counting a year as 365 days and a
+    /// month as 30 days. Returns `None` on overflow.
+    #[cfg(feature = "chrono")]
+    pub fn to_chrono_duration_approx(self) -> Option<chrono::Duration> {
+        let secs = i64::try_from(self.total_secs_approx()?).ok()?;
+        let duration = chrono::Duration::try_seconds(secs)?.checked_add(
+            &chrono::Duration::nanoseconds((self.nanoseconds % 1_000_000_000).into()),
+        )?;
+        Some(if self.negative { -duration } else { duration })
+    }
+}
+
+impl PartialEq for XsDuration {
+    fn eq(&self, other: &Self) -> bool {
+        self.months_and_nanos() == other.months_and_nanos()
+    }
+}
+
+impl Eq for XsDuration {}
+
+impl Hash for XsDuration {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.months_and_nanos().hash(state);
+    }
+}
+
+/// Days from 1970-01-01 to the first day of `month` (1 to 12) of `year` in the
+/// proleptic Gregorian calendar.
+fn days_from_civil(year: i128, month: i128) -> i128 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let day_of_year = (153 * ((month + 9) % 12) + 2) / 5;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146_097 + day_of_era - 719_468
+}
+
+impl XsDuration {
+    /// Reference dateTimes of XML Schema Part 2 appendix E.3.3, as (year, month)
+    /// on the first day of the month at midnight UTC.
+    const ORDER_REFERENCES: [(i128, i128); 4] = [(1696, 9), (1697, 2), (1903, 3), (1903, 7)];
+
+    /// Nanoseconds since 1970-01-01 of the first day of `month` of `year` plus
+    /// this duration.
+    fn instant_from(&self, year: i128, month: i128) -> i128 {
+        let (months, nanos) = self.months_and_nanos();
+        let month_index = year * 12 + month - 1 + months;
+        let days = days_from_civil(month_index.div_euclid(12), month_index.rem_euclid(12) + 1);
+        days * i128::from(Self::SECS_PER_DAY) * 1_000_000_000 + nanos
+    }
+}
+
+/// Durations are compared by adding each to the four reference dateTimes of
+/// XML Schema, so `P1M > P1D` and `P1Y < P400D`. They are unordered when the
+/// references disagree, e.g. `P1M` and `P30D`.
+impl PartialOrd for XsDuration {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        let mut orderings = Self::ORDER_REFERENCES.iter().map(|&(year, month)| {
+            self.instant_from(year, month)
+                .cmp(&other.instant_from(year, month))
+        });
+        let first = orderings.next()?;
+        orderings.all(|ordering| ordering == first).then_some(first)
+    }
+}
+
+impl Neg for XsDuration {
+    type Output = Self;
+
+    fn neg(self) -> Self::Output {
+        Self {
+            negative: !self.negative,
+            ..self
+        }
+    }
+}
+
+impl Add for XsDuration {
+    type Output = Self;
+
+    /// # Panics
+    ///
+    /// Panics if the sum has a months part and a seconds part of opposite
+    /// signs, e.g. `P1M + -P1D`, or overflows. Use [`XsDuration::checked_add`]
+    /// or [`XsDuration::saturating_add`] to handle those cases.
+    fn add(self, rhs: Self) -> Self::Output {
+        self.checked_add(rhs)
+            .expect("overflow or mixed signs when adding durations")
+    }
+}
+
+impl Sub for XsDuration {
+    type Output = Self;
+
+    /// # Panics
+    ///
+    /// Panics if the difference has a months part and a seconds part of
+    /// opposite signs, e.g. `P1M - P1D`, or overflows. Use
+    /// [`XsDuration::checked_sub`] or [`XsDuration::saturating_sub`] to handle
+    /// those cases.
+    fn sub(self, rhs: Self) -> Self::Output {
+        self.checked_sub(rhs)
+            .expect("overflow or mixed signs when subtracting durations")
+    }
+}
+
+impl Mul<u32> for XsDuration {
+    type Output = Self;
+
+    /// # Panics
+    ///
+    /// Panics if a component overflows. Use [`XsDuration::checked_mul`] to
+    /// handle that case.
+    fn mul(self, rhs: u32) -> Self::Output {
+        self.checked_mul(rhs)
+            .expect("overflow when multiplying duration")
+    }
+}
+
+impl FromStr for XsDuration {
+    type Err = MpdError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let err = || MpdError::InvalidValue {
+            name: "XsDuration",
+            value: s.to_string(),
+        };
+        let caps = PATTERN_XS_DURATION.captures(s).ok_or_else(err)?;
+        // `P` and `T` must each be followed by at least one component.
+        if s.ends_with('P') || s.ends_with('T') {
+            return Err(err());
+        }
+
+        let component = |i: usize| -> Result<u64, MpdError> {
+            caps.get(i)
+                .map_or(Ok(0), |m| m.as_str().parse::<u64>().map_err(|_| err()))
+        };
+        // Digits beyond nanosecond precision are truncated.
+        let nanoseconds = caps.get(8).map_or(0, |m| {
+            let digits = &m.as_str()[..m.as_str().len().min(9)];
+            format!("{:0<9}", digits).parse::<u32>().unwrap_or_default()
+        });
+
+        Ok(Self {
+            negative: caps.get(1).is_some(),
+            years: component(2)?,
+            months: component(3)?,
+            days: component(4)?,
+            hours: component(5)?,
+            minutes: component(6)?,
+            seconds: component(7)?,
+            nanoseconds,
+        })
+    }
+}
+
+impl fmt::Display for XsDuration {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_zero() {
+            return write!(f, "P0D");
+        }
+        if self.negative {
+            write!(f, "-")?;
+        }
+        write!(f, "P")?;
+        if self.years > 0 {
+            write!(f, "{}Y", self.years)?;
+        }
+        if self.months > 0 {
+            write!(f, "{}M", self.months)?;
+        }
+        if self.days > 0 {
+            write!(f, "{}D", self.days)?;
+        }
+        let seconds = u128::from(self.seconds) + u128::from(self.nanoseconds / 1_000_000_000);
+        let nanoseconds = self.nanoseconds % 1_000_000_000;
+        if self.hours > 0 || self.minutes > 0 || seconds > 0 || nanoseconds > 0 {
+            write!(f, "T")?;
+        }
+        if self.hours > 0 {
+            write!(f, "{}H", self.hours)?;
+        }
+        if self.minutes > 0 {
+            write!(f, "{}M", self.minutes)?;
+        }
+        if seconds > 0 || nanoseconds > 0 {
+            write!(f, "{}", seconds)?;
+            if nanoseconds > 0 {
+                let fraction = format!("{:09}", nanoseconds);
+                write!(f, ".{}", fraction.trim_end_matches('0'))?;
+            }
+            write!(f, "S")?;
+        }
+        Ok(())
+    }
+}
+
+impl From<iso8601::Duration> for XsDuration {
+    fn from(value: iso8601::Duration) -> Self {
+        match value {
+            iso8601::Duration::YMDHMS {
+                year,
+                month,
+                day,
+                hour,
+                minute,
+                second,
+                millisecond,
+            } => Self {
+                years: year.into(),
+                months: month.into(),
+                days: day.into(),
+                hours: hour.into(),
+                minutes: minute.into(),
+                seconds: second.into(),
+                nanoseconds: millisecond * 1_000_000,
+                ..Default::default()
+            },
+            iso8601::Duration::Weeks(weeks) => Self {
+                days: u64::from(weeks) * 7,
+                ..Default::default()
+            },
+        }
+    }
+}
+
+impl From<std::time::Duration> for XsDuration {
+    fn from(value: std::time::Duration) -> Self {
+        Self {
+            seconds: value.as_secs(),
+            nanoseconds: value.subsec_nanos(),
+            ..Default::default()
+        }
+    }
+}
+
+#[cfg(feature = "chrono")]
+impl From<chrono::Duration> for XsDuration {
+    fn from(value: chrono::Duration) -> Self {
+        let abs = value.abs();
+        Self {
+            negative: value < chrono::Duration::zero(),
+            seconds: abs.num_seconds().unsigned_abs(),
+            nanoseconds: abs.subsec_nanos().unsigned_abs(),
+            ..Default::default()
+        }
     }
 }
 
+/// Invalid input yields a zero duration.
 impl From<&[u8]> for XsDuration {
     fn from(value: &[u8]) -> Self {
-        Self(
-            iso8601::parsers::parse_duration(value)
-                .and_then(|(_, duration)| Ok(duration))
-                .unwrap_or_default(),
-        )
+        std::str::from_utf8(value)
+            .ok()
+            .and_then(|s| s.parse().ok())
+            .unwrap_or_default()
     }
 }
 
@@ -37,25 +476,24 @@ impl From<&str> for XsDuration {
     }
 }
 
+#[cfg(feature = "generate")]
 impl Serialize for XsDuration {
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
     where
         S: Serializer,
     {
-        serializer.serialize_str(&self.0.to_string())
+        serializer.serialize_str(&self.to_string())
     }
 }
 
+#[cfg(feature = "parse")]
 impl<'de> Deserialize<'de> for XsDuration {
     fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
     where
         D: Deserializer<'de>,
     {
         let s = String::deserialize(deserializer)?;
-        let duration = s
-            .parse::<iso8601::Duration>()
-            .map_err(serde::de::Error::custom)?;
-        Ok(XsDuration(duration))
+        s.parse::<XsDuration>().map_err(serde::de::Error::custom)
     }
 }
 
@@ -88,6 +526,7 @@ impl From<i64> for XsInteger {
     }
 }
 
+#[cfg(feature = "generate")]
 impl Serialize for XsInteger {
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
     where
@@ -97,6 +536,7 @@ impl Serialize for XsInteger {
     }
 }
 
+#[cfg(feature = "parse")]
 impl<'de> Deserialize<'de> for XsInteger {
     fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
     where
@@ -108,6 +548,108 @@ impl<'de> Deserialize<'de> for XsInteger {
     }
 }
 
+macro_rules! ranged_newtype {
+    ($(#[$meta:meta])* $name:ident($inner:ty), $range:expr) => {
+        $(#[$meta])*
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+        pub struct $name($inner);
+
+        impl $name {
+            pub const RANGE: RangeInclusive<$inner> = $range;
+
+            pub fn new(value: $inner) -> Result<Self, MpdError> {
+                if Self::RANGE.contains(&value) {
+                    Ok(Self(value))
+                } else {
+                    Err(MpdError::OutOfRange {
+                        name: stringify!($name),
+                        value: value.to_string(),
+                    })
+                }
+            }
+        }
+
+        impl Deref for $name {
+            type Target = $inner;
+
+            fn deref(&self) -> &Self::Target {
+                &self.0
+            }
+        }
+
+        impl TryFrom<$inner> for $name {
+            type Error = MpdError;
+
+            fn try_from(value: $inner) -> Result<Self, Self::Error> {
+                Self::new(value)
+            }
+        }
+
+        impl From<$name> for $inner {
+            fn from(value: $name) -> Self {
+                value.0
+            }
+        }
+
+        impl FromStr for $name {
+            type Err = MpdError;
+
+            fn from_str(s: &str) -> Result<Self, Self::Err> {
+                let value = s.parse::<$inner>().map_err(|_| MpdError::InvalidValue {
+                    name: stringify!($name),
+                    value: s.to_string(),
+                })?;
+                Self::new(value)
+            }
+        }
+
+        impl fmt::Display for $name {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                self.0.fmt(f)
+            }
+        }
+
+        #[cfg(feature = "generate")]
+        impl Serialize for $name {
+            fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+            where
+                S: Serializer,
+            {
+                serializer.serialize_str(&self.0.to_string())
+            }
+        }
+
+        #[cfg(feature = "parse")]
+        impl<'de> Deserialize<'de> for $name {
+            fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+            where
+                D: Deserializer<'de>,
+            {
+                let s = String::deserialize(deserializer)?;
+                s.parse::<$name>().map_err(serde::de::Error::custom)
+            }
+        }
+    };
+}
+
+ranged_newtype!(
+    /// `xs:unsignedInt` bandwidth in bits per second, must be greater than zero.
+    Bandwidth(u32),
+    1..=u32::MAX
+);
+
+ranged_newtype!(
+    /// `xs:unsignedInt` number of time units per second, must be greater than zero.
+    Timescale(u32),
+    1..=u32::MAX
+);
+
+ranged_newtype!(
+    /// Percentage in the range 1 to 100, as used by `@popularityRate`.
+    Percentage(u8),
+    1..=100
+);
+
 #[derive(Debug, Default, Clone, PartialEq, Eq, Hash)]
 pub struct SingleRFC7233RangeType {
     pub start: Option<u64>,
@@ -123,6 +665,7 @@ impl From<(Option<u64>, Option<u64>)> for SingleRFC7233RangeType {
     }
 }
 
+#[cfg(feature = "generate")]
 impl Serialize for SingleRFC7233RangeType {
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
     where
@@ -139,39 +682,227 @@ impl Serialize for SingleRFC7233RangeType {
     }
 }
 
+/// The empty string parses to a range without bounds, which is how such a
+/// range is serialized. `-` alone, bounds that overflow `u64` and a start past
+/// the end are rejected.
+impl FromStr for SingleRFC7233RangeType {
+    type Err = MpdError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let err = || MpdError::InvalidValue {
+            name: "SingleRFC7233RangeType",
+            value: s.to_string(),
+        };
+        let caps = PATTERN_SINGLE_RFC7233_RANGE.captures(s).ok_or_else(err)?;
+        let bound = |i: usize| match caps.get(i).map(|m| m.as_str()) {
+            None | Some("") => Ok(None),
+            Some(digits) => digits.parse::<u64>().map(Some).map_err(|_| err()),
+        };
+        let (start, end) = (bound(1)?, bound(3)?);
+        if caps.get(2).is_some() && start.is_none() && end.is_none() {
+            return Err(err());
+        }
+        if let (Some(start), Some(end)) = (start, end) {
+            if start > end {
+                return Err(err());
+            }
+        }
+        Ok(SingleRFC7233RangeType { start, end })
+    }
+}
+
+#[cfg(feature = "parse")]
 impl<'de> Deserialize<'de> for SingleRFC7233RangeType {
     fn deserialize<D>(deserializer: D) -> Result<SingleRFC7233RangeType, D::Error>
     where
         D: Deserializer<'de>,
     {
         let s = String::deserialize(deserializer)?;
-        let re = Regex::new(r"^([0-9]*)(-([0-9]*))?$").unwrap();
-        if let Some(caps) = re.captures(&s) {
-            let start = caps.get(1).and_then(|m| {
-                if m.as_str().is_empty() {
-                    None
-                } else {
-                    m.as_str().parse::<u64>().ok()
-                }
-            });
-            let end = caps.get(3).and_then(|m| {
-                if m.as_str().is_empty() {
-                    None
-                } else {
-                    m.as_str().parse::<u64>().ok()
-                }
-            });
-            Ok(SingleRFC7233RangeType { start, end })
+        s.parse::<SingleRFC7233RangeType>()
+            .map_err(serde::de::Error::custom)
+    }
+}
+
+/// `xs:anyURI`.
+///
+/// Parsing accepts characters that would be percent-encoded when the URI is
+/// used, such as a space, as `xs:anyURI` does. It still rejects malformed
+/// percent-encodings, a second `#` and an invalid scheme. Use
+/// [`XsAnyUri::validate`] for the strict RFC 3986 check.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Hash)]
+pub struct XsAnyUri(String);
+
+/// Components of a URI reference as split by RFC 3986 Appendix B.
+struct UriComponents<'a> {
+    scheme: Option<&'a str>,
+    authority: Option<&'a str>,
+    path: &'a str,
+    query: Option<&'a str>,
+    fragment: Option<&'a str>,
+}
+
+impl<'a> UriComponents<'a> {
+    fn parse(s: &'a str) -> Self {
+        // The Appendix B expression matches every string.
+        let caps = PATTERN_URI_REFERENCE.captures(s).unwrap();
+        let get = |i: usize| caps.get(i).map(|m| m.as_str());
+        Self {
+            scheme: get(2),
+            authority: get(4),
+            path: get(5).unwrap_or_default(),
+            query: get(7),
+            fragment: get(9),
+        }
+    }
+
+    /// Recomposes the components as described in RFC 3986 section 5.3.
+    fn recompose(&self, path: &str) -> String {
+        let mut s = String::new();
+        if let Some(scheme) = self.scheme {
+            s.push_str(scheme);
+            s.push(':');
+        }
+        if let Some(authority) = self.authority {
+            s.push_str("//");
+            s.push_str(authority);
+        }
+        s.push_str(path);
+        if let Some(query) = self.query {
+            s.push('?');
+            s.push_str(query);
+        }
+        if let Some(fragment) = self.fragment {
+            s.push('#');
+            s.push_str(fragment);
+        }
+        s
+    }
+}
+
+/// RFC 3986 section 5.2.4.
+fn remove_dot_segments(path: &str) -> String {
+    fn pop_segment(output: &mut String) {
+        let len = output.rfind('/').unwrap_or_default();
+        output.truncate(len);
+    }
+
+    let mut input = path;
+    let mut output = String::new();
+    while !input.is_empty() {
+        if let Some(rest) = input.strip_prefix("../") {
+            input = rest;
+        } else if let Some(rest) = input.strip_prefix("./") {
+            input = rest;
+        } else if input.starts_with("/./") {
+            input = &input[2..];
+        } else if input == "/." {
+            input = "/";
+        } else if input.starts_with("/../") {
+            input = &input[3..];
+            pop_segment(&mut output);
+        } else if input == "/.." {
+            input = "/";
+            pop_segment(&mut output);
+        } else if input == "." || input == ".." {
+            input = "";
         } else {
-            Err(serde::de::Error::custom(
-                "Invalid format for SingleRFC7233RangeType",
-            ))
+            let start = usize::from(input.starts_with('/'));
+            let end = input[start..].find('/').map_or(input.len(), |i| i + start);
+            output.push_str(&input[..end]);
+            input = &input[end..];
         }
     }
+    output
 }
 
-#[derive(Debug, Default, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
-pub struct XsAnyUri(String);
+impl XsAnyUri {
+    /// Checks that the value is an RFC 3986 URI reference, rejecting characters
+    /// such as a space that parsing lets through. RFC 3987 IRI characters are
+    /// accepted.
+    pub fn validate(&self) -> Result<(), MpdError> {
+        let valid = self.0.bytes().all(|b| {
+            !b.is_ascii() || b.is_ascii_alphanumeric() || b"-._~:/?#[]@!$&'()*+,;=%".contains(&b)
+        });
+        if valid {
+            Ok(())
+        } else {
+            Err(MpdError::InvalidValue {
+                name: "XsAnyUri",
+                value: self.0.clone(),
+            })
+        }
+    }
+
+    /// Returns true if the reference has a scheme, so it does not depend on a
+    /// base URI.
+    pub fn is_absolute(&self) -> bool {
+        UriComponents::parse(&self.0).scheme.is_some()
+    }
+
+    /// Resolves `reference` against `self` following RFC 3986 section 5.2.
+    ///
+    /// `self` may itself be relative, in which case the result is relative too.
+    pub fn join(&self, reference: &XsAnyUri) -> XsAnyUri {
+        let base = UriComponents::parse(&self.0);
+        let reference = UriComponents::parse(&reference.0);
+
+        let (target, path) = if reference.scheme.is_some() {
+            let path = remove_dot_segments(reference.path);
+            (reference, path)
+        } else if reference.authority.is_some() {
+            let path = remove_dot_segments(reference.path);
+            (
+                UriComponents {
+                    scheme: base.scheme,
+                    ..reference
+                },
+                path,
+            )
+        } else if reference.path.is_empty() {
+            (
+                UriComponents {
+                    scheme: base.scheme,
+                    authority: base.authority,
+                    path: "",
+                    query: reference.query.or(base.query),
+                    fragment: reference.fragment,
+                },
+                base.path.to_string(),
+            )
+        } else {
+            let path = if reference.path.starts_with('/') {
+                remove_dot_segments(reference.path)
+            } else if base.authority.is_some() && base.path.is_empty() {
+                remove_dot_segments(&format!("/{}", reference.path))
+            } else {
+                let dir = base.path.rfind('/').map_or("", |i| &base.path[..=i]);
+                remove_dot_segments(&format!("{}{}", dir, reference.path))
+            };
+            (
+                UriComponents {
+                    scheme: base.scheme,
+                    authority: base.authority,
+                    ..reference
+                },
+                path,
+            )
+        };
+
+        XsAnyUri(target.recompose(&path))
+    }
+
+    /// Iterates over the `key=value` pairs of the query component. Pairs are
+    /// returned as written, without percent-decoding; a pair without `=` has
+    /// an empty value.
+    pub fn query_pairs(&self) -> impl Iterator<Item = (&str, &str)> {
+        UriComponents::parse(&self.0)
+            .query
+            .into_iter()
+            .flat_map(|query| query.split('&'))
+            .filter(|pair| !pair.is_empty())
+            .map(|pair| pair.split_once('=').unwrap_or((pair, "")))
+    }
+}
 
 impl Deref for XsAnyUri {
     type Target = String;
@@ -181,7 +912,72 @@ impl Deref for XsAnyUri {
     }
 }
 
-#[derive(Debug, Default, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
+impl FromStr for XsAnyUri {
+    type Err = MpdError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let err = || MpdError::InvalidValue {
+            name: "XsAnyUri",
+            value: s.to_string(),
+        };
+
+        let bytes = s.as_bytes();
+        for (i, &b) in bytes.iter().enumerate() {
+            let valid = match b {
+                b'%' => {
+                    bytes.get(i + 1).is_some_and(u8::is_ascii_hexdigit)
+                        && bytes.get(i + 2).is_some_and(u8::is_ascii_hexdigit)
+                }
+                b'#' => s[i + 1..].find('#').is_none(),
+                _ => true,
+            };
+            if !valid {
+                return Err(err());
+            }
+        }
+
+        if let Some(scheme) = UriComponents::parse(s).scheme {
+            if !PATTERN_URI_SCHEME.is_match(scheme) {
+                return Err(err());
+            }
+        }
+
+        Ok(Self(s.to_string()))
+    }
+}
+
+impl TryFrom<&str> for XsAnyUri {
+    type Error = MpdError;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
+#[cfg(feature = "generate")]
+impl Serialize for XsAnyUri {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(&self.0)
+    }
+}
+
+#[cfg(feature = "parse")]
+impl<'de> Deserialize<'de> for XsAnyUri {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let s = String::deserialize(deserializer)?;
+        s.parse::<XsAnyUri>().map_err(serde::de::Error::custom)
+    }
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "generate", derive(Serialize))]
+#[cfg_attr(feature = "parse", derive(Deserialize))]
 #[serde(rename = "URLType")]
 pub struct Url {
     #[serde(rename = "@sourceURL", skip_serializing_if = "Option::is_none")]
@@ -190,7 +986,34 @@ pub struct Url {
     pub range: Option<SingleRFC7233RangeType>,
 }
 
-#[derive(Debug, Default, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
+impl From<(Option<XsAnyUri>, Option<SingleRFC7233RangeType>)> for Url {
+    fn from(value: (Option<XsAnyUri>, Option<SingleRFC7233RangeType>)) -> Self {
+        Self {
+            source_url: value.0,
+            range: value.1,
+        }
+    }
+}
+
+/// A `(None, None)` range leaves `@range` absent.
+impl TryFrom<(&str, (Option<u64>, Option<u64>))> for Url {
+    type Error = MpdError;
+
+    fn try_from(value: (&str, (Option<u64>, Option<u64>))) -> Result<Self, Self::Error> {
+        let range = match value.1 {
+            (None, None) => None,
+            range => Some(range.into()),
+        };
+        Ok(Self {
+            source_url: Some(value.0.parse()?),
+            range,
+        })
+    }
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "generate", derive(Serialize))]
+#[cfg_attr(feature = "parse", derive(Deserialize))]
 #[serde(rename = "FCS")]
 pub struct Fcs {
     #[serde(rename = "@t")]
@@ -199,19 +1022,130 @@ pub struct Fcs {
     pub duration: Option<u64>,
 }
 
-#[derive(Debug, Default, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
+impl From<(u64, Option<u64>)> for Fcs {
+    fn from(value: (u64, Option<u64>)) -> Self {
+        Self {
+            start_time: value.0,
+            duration: value.1,
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Eq, Hash, Builder)]
+#[cfg_attr(feature = "generate", derive(Serialize))]
+#[cfg_attr(feature = "parse", derive(Deserialize))]
+#[builder(setter(into, strip_option), default)]
 #[serde(rename = "FailoverContent")]
 pub struct FailoverContent {
-    #[serde(rename = "@valid")]
+    #[serde(rename = "@valid", skip_serializing_if = "Option::is_none")]
     pub valid: Option<bool>,
+    #[builder(setter(custom))]
     #[serde(rename = "FCS", skip_serializing_if = "Vec::is_empty")]
     pub fcs_list: Vec<Fcs>,
 }
 
+/// Times are presentation times in the `@timescale` of the enclosing segment
+/// information, like `FCS@t`.
+impl FailoverContent {
+    /// Returns true if failover content is signalled at `time`.
+    ///
+    /// With `@valid` true (the default) the `FCS` ranges are failover content;
+    /// with `@valid` false everything outside them is.
+    pub fn covers(&self, time: u64) -> bool {
+        let listed = self.fcs_ranges().iter().any(|range| range.contains(&time));
+        listed == self.valid.unwrap_or(true)
+    }
+
+    /// Sub-ranges of `start..end` that are not covered by failover content.
+    pub fn gaps_between(&self, start: u64, end: u64) -> Vec<Range<u64>> {
+        if start >= end {
+            return Vec::new();
+        }
+        let clipped = self
+            .fcs_ranges()
+            .into_iter()
+            .map(|range| range.start.max(start)..range.end.min(end))
+            .filter(|range| !range.is_empty());
+
+        if !self.valid.unwrap_or(true) {
+            return clipped.collect();
+        }
+
+        let mut gaps = Vec::new();
+        let mut cursor = start;
+        for range in clipped {
+            if range.start > cursor {
+                gaps.push(cursor..range.start);
+            }
+            cursor = cursor.max(range.end);
+        }
+        if cursor < end {
+            gaps.push(cursor..end);
+        }
+        gaps
+    }
+
+    /// `FCS` time ranges, sorted and merged. An `FCS` without `@d` extends to
+    /// the next `FCS@t`, or indefinitely if it is the last one.
+    fn fcs_ranges(&self) -> Vec<Range<u64>> {
+        let mut fcs_list = self.fcs_list.iter().collect::<Vec<_>>();
+        fcs_list.sort_by_key(|fcs| fcs.start_time);
+
+        let mut ranges: Vec<Range<u64>> = Vec::with_capacity(fcs_list.len());
+        for (i, fcs) in fcs_list.iter().enumerate() {
+            let end = match fcs.duration {
+                Some(duration) => fcs.start_time.saturating_add(duration),
+                None => fcs_list.get(i + 1).map_or(u64::MAX, |next| next.start_time),
+            };
+            match ranges.last_mut() {
+                Some(last) if fcs.start_time <= last.end => last.end = last.end.max(end),
+                _ => ranges.push(fcs.start_time..end),
+            }
+        }
+        ranges
+    }
+}
+
+impl FailoverContentBuilder {
+    pub fn fcs<F: Into<Fcs>>(&mut self, fcs: F) -> &mut Self {
+        self.fcs_list.get_or_insert_with(Vec::new).push(fcs.into());
+        self
+    }
+
+    pub fn fcs_list<F>(&mut self, fcs_list: F) -> &mut Self
+    where
+        F: IntoIterator,
+        F::Item: Into<Fcs>,
+    {
+        self.fcs_list
+            .get_or_insert_with(Vec::new)
+            .extend(fcs_list.into_iter().map(Into::into));
+        self
+    }
+}
+
+maybe_setters!(FailoverContentBuilder {
+    valid => maybe_valid: bool,
+});
+
+impl<F> From<(Option<bool>, F)> for FailoverContent
+where
+    F: IntoIterator,
+    F::Item: Into<Fcs>,
+{
+    fn from(value: (Option<bool>, F)) -> Self {
+        Self {
+            valid: value.0,
+            fcs_list: value.1.into_iter().map(Into::into).collect(),
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[cfg(all(feature = "parse", feature = "generate"))]
     #[test]
     fn test_types_xs_integer_serde() {
         let value = 10000;
@@ -225,27 +1159,195 @@ mod tests {
         assert!(der.is_ok_and(|val| val == xs_integer));
     }
 
+    #[cfg(all(feature = "parse", feature = "generate"))]
     #[test]
     fn test_types_xs_duration_serde() {
         let value = "foo";
         let xs_duration = XsDuration::from(value);
-        assert!(*xs_duration == iso8601::Duration::default());
+        assert!(xs_duration == XsDuration::default());
 
         let value = "PT3H11M53S";
         let xs_duration = XsDuration::from(value);
         let ser = serde_plain::to_string(&xs_duration).unwrap();
 
-        assert!(&ser == value);
+        assert!(ser == value);
 
         let der = serde_plain::from_str::<XsDuration>(&ser);
 
         assert!(der.is_ok_and(|val| val == xs_duration));
     }
 
+    #[test]
+    fn test_types_xs_duration_calendar_components() {
+        for value in [
+            "P1M",
+            "-P1Y2M3DT4H5M6.789S",
+            "PT0.000000001S",
+            "PT36H",
+            "PT3600S",
+        ] {
+            let xs_duration = value.parse::<XsDuration>().unwrap();
+            assert_eq!(xs_duration.to_string(), value);
+        }
+
+        let xs_duration = "P1M".parse::<XsDuration>().unwrap();
+        assert_eq!(xs_duration.months, 1);
+        assert_eq!(xs_duration.seconds, 0);
+
+        let xs_duration = "PT1.50S".parse::<XsDuration>().unwrap();
+        assert_eq!(xs_duration.nanoseconds, 500_000_000);
+        assert_eq!(xs_duration.to_string(), "PT1.5S");
+
+        let xs_duration = XsDuration {
+            seconds: 1,
+            nanoseconds: 2_500_000_000,
+            ..Default::default()
+        };
+        assert_eq!(xs_duration.to_string(), "PT3.5S");
+        assert_eq!(xs_duration, XsDuration::from("PT3.5S"));
+        assert_eq!(xs_duration.to_string().parse(), Ok(xs_duration));
+    }
+
+    #[test]
+    fn test_types_xs_duration_invalid() {
+        for value in ["", "P", "PT", "P1DT", "1D", "P1W", "P-1D", "PT1.S"] {
+            assert!(value.parse::<XsDuration>().is_err(), "{value}");
+        }
+    }
+
+    #[test]
+    fn test_types_xs_duration_to_std_approx() {
+        let xs_duration = XsDuration::from("P1DT1.5S");
+        assert_eq!(
+            xs_duration.to_std_duration_approx(),
+            Some(std::time::Duration::from_millis(86_401_500))
+        );
+
+        let xs_duration = XsDuration::from("-PT1S");
+        assert_eq!(xs_duration.to_std_duration_approx(), None);
+
+        let xs_duration = XsDuration {
+            seconds: 1,
+            nanoseconds: 2_500_000_000,
+            ..Default::default()
+        };
+        assert_eq!(
+            xs_duration.to_std_duration_approx(),
+            Some(std::time::Duration::from_millis(3_500))
+        );
+
+        let xs_duration = XsDuration {
+            seconds: u64::MAX,
+            nanoseconds: 2_000_000_000,
+            ..Default::default()
+        };
+        assert_eq!(xs_duration.to_std_duration_approx(), None);
+    }
+
+    #[test]
+    fn test_types_xs_duration_arithmetic() {
+        let a = XsDuration::from("PT90M");
+        let b = XsDuration::from("PT45M");
+
+        assert_eq!((a + b).to_string(), "PT2H15M");
+        assert_eq!((b - a).to_string(), "-PT45M");
+        assert_eq!((a * 2).to_string(), "PT3H");
+        assert_eq!((-a + a).to_string(), "P0D");
+        assert_eq!(
+            (XsDuration::from("P1Y") + XsDuration::from("P13M")).to_string(),
+            "P2Y1M"
+        );
+
+        assert!(XsDuration::from("P1M")
+            .checked_sub(XsDuration::from("P1D"))
+            .is_none());
+        assert_eq!(
+            XsDuration::from("P1M").saturating_sub(XsDuration::from("P1D")),
+            XsDuration::from("P1M")
+        );
+        assert_eq!(
+            XsDuration::from("PT2H").saturating_sub(XsDuration::from("P1MT1H")),
+            XsDuration::from("-P1M")
+        );
+        assert_eq!(
+            XsDuration::from("P18446744073709551615Y11M")
+                .saturating_add(XsDuration::from("P1M"))
+                .to_string(),
+            "P18446744073709551615Y11M"
+        );
+    }
+
+    #[test]
+    fn test_types_xs_duration_ordering() {
+        assert_eq!(XsDuration::from("PT60S"), XsDuration::from("PT1M"));
+        assert!(XsDuration::from("PT1M") < XsDuration::from("PT61S"));
+        assert!(XsDuration::from("-PT1H") < XsDuration::from("PT0S"));
+        assert!(XsDuration::from("P1Y1D") > XsDuration::from("P1Y"));
+        assert_eq!(
+            XsDuration::from("P1M").partial_cmp(&XsDuration::from("P30D")),
+            None
+        );
+        assert!(XsDuration::from("P1M") > XsDuration::from("P1D"));
+        assert!(XsDuration::from("P1Y") < XsDuration::from("P400D"));
+        assert!(XsDuration::from("P1Y") > XsDuration::from("P364D"));
+        assert!(XsDuration::from("-P1M") < XsDuration::from("-P27D"));
+        assert!(XsDuration::from("P1M") < XsDuration::from("P1DT1H").checked_mul(30).unwrap());
+        assert_eq!(
+            XsDuration::from("P1Y").partial_cmp(&XsDuration::from("P365D")),
+            None
+        );
+        assert_eq!(
+            XsDuration::from("P1Y1M").partial_cmp(&XsDuration::from("P13M")),
+            Some(Ordering::Equal)
+        );
+    }
+
+    #[cfg(feature = "chrono")]
+    #[test]
+    fn test_types_xs_duration_chrono() {
+        let xs_duration = XsDuration::from("-PT1M0.5S");
+        let duration = xs_duration.to_chrono_duration_approx().unwrap();
+
+        assert_eq!(duration, chrono::Duration::milliseconds(-60_500));
+        assert_eq!(XsDuration::from(duration).to_string(), "-PT60.5S");
+    }
+
+    #[cfg(all(feature = "parse", feature = "generate"))]
+    #[test]
+    fn test_types_ranged_newtype_serde() {
+        let timescale = Timescale::new(90000).unwrap();
+        let ser = serde_plain::to_string(&timescale).unwrap();
+
+        assert_eq!(ser, "90000");
+
+        let der = serde_plain::from_str::<Timescale>(&ser);
+
+        assert!(der.is_ok_and(|val| val == timescale));
+    }
+
+    #[test]
+    fn test_types_ranged_newtype_out_of_range() {
+        assert!(Timescale::new(0).is_err());
+        assert!(Bandwidth::try_from(0).is_err());
+        assert!(Percentage::new(0).is_err());
+        assert!(Percentage::new(101).is_err());
+        assert!(Percentage::new(100).is_ok());
+
+        assert!("-1".parse::<Timescale>().is_err());
+        assert!("abc".parse::<Percentage>().is_err());
+    }
+
+    #[cfg(feature = "parse")]
+    #[test]
+    fn test_types_ranged_newtype_de_out_of_range() {
+        assert!(serde_plain::from_str::<Bandwidth>("0").is_err());
+    }
+
+    #[cfg(all(feature = "parse", feature = "generate"))]
     #[test]
     fn test_types_single_range_type_serde_full() {
         let plain = "100-200";
-        let result = serde_plain::from_str::<SingleRFC7233RangeType>(&plain).unwrap();
+        let result = serde_plain::from_str::<SingleRFC7233RangeType>(plain).unwrap();
 
         assert_eq!(
             result,
@@ -260,10 +1362,11 @@ mod tests {
         assert_eq!(plain, ser.as_str());
     }
 
+    #[cfg(all(feature = "parse", feature = "generate"))]
     #[test]
     fn test_types_single_range_type_serde_start_only() {
         let plain = "100-";
-        let result = serde_plain::from_str::<SingleRFC7233RangeType>(&plain).unwrap();
+        let result = serde_plain::from_str::<SingleRFC7233RangeType>(plain).unwrap();
 
         assert_eq!(
             result,
@@ -278,10 +1381,11 @@ mod tests {
         assert_eq!(plain, ser.as_str());
     }
 
+    #[cfg(all(feature = "parse", feature = "generate"))]
     #[test]
     fn test_types_single_range_type_serde_end_only() {
         let plain = "-200";
-        let result = serde_plain::from_str::<SingleRFC7233RangeType>(&plain).unwrap();
+        let result = serde_plain::from_str::<SingleRFC7233RangeType>(plain).unwrap();
 
         assert_eq!(
             result,
@@ -296,10 +1400,11 @@ mod tests {
         assert_eq!(plain, ser.as_str());
     }
 
+    #[cfg(all(feature = "parse", feature = "generate"))]
     #[test]
     fn test_types_single_range_type_serde_empty() {
         let plain = "";
-        let result = serde_plain::from_str::<SingleRFC7233RangeType>(&plain).unwrap();
+        let result = serde_plain::from_str::<SingleRFC7233RangeType>(plain).unwrap();
 
         assert_eq!(
             result,
@@ -314,19 +1419,113 @@ mod tests {
         assert_eq!(plain, ser.as_str());
     }
 
+    #[test]
+    fn test_types_single_range_type_from_str_invalid() {
+        assert!("100-100".parse::<SingleRFC7233RangeType>().is_ok());
+        for value in [
+            "-",
+            "99999999999999999999999-100",
+            "100-99999999999999999999999",
+            "200-100",
+        ] {
+            assert_eq!(
+                value.parse::<SingleRFC7233RangeType>(),
+                Err(MpdError::InvalidValue {
+                    name: "SingleRFC7233RangeType",
+                    value: value.to_string()
+                })
+            );
+        }
+    }
+
+    #[cfg(feature = "parse")]
     #[test]
     fn test_types_single_range_type_invalid_format() {
         let plain = "abc-xyz";
-        let result = serde_plain::from_str::<SingleRFC7233RangeType>(&plain);
+        let result = serde_plain::from_str::<SingleRFC7233RangeType>(plain);
 
         assert!(result.is_err());
     }
 
+    #[test]
+    fn test_types_xs_any_uri_validation() {
+        for value in [
+            "http://example.com/a%20b?x=1#top",
+            "video/seg.mp4",
+            "",
+            "urn:mpeg:dash",
+        ] {
+            let uri = value.parse::<XsAnyUri>().unwrap();
+            assert_eq!(uri.validate(), Ok(()), "{value}");
+        }
+        for value in ["http://example.com/a b", "<x>", "a|b{c}^`"] {
+            let uri = value.parse::<XsAnyUri>().unwrap();
+            assert!(uri.validate().is_err(), "{value}");
+        }
+        for value in ["a%2", "a#b#c", "1http://x"] {
+            assert!(value.parse::<XsAnyUri>().is_err(), "{value}");
+        }
+    }
+
+    #[cfg(feature = "parse")]
+    #[test]
+    fn test_types_xs_any_uri_de() {
+        let url =
+            quick_xml::de::from_str::<Url>(r#"<URLType sourceURL="http://a/b c.mp4"/>"#).unwrap();
+
+        assert_eq!(url.source_url.unwrap().as_str(), "http://a/b c.mp4");
+        assert!(quick_xml::de::from_str::<Url>(r#"<URLType sourceURL="a%zz"/>"#).is_err());
+    }
+
+    #[test]
+    fn test_types_xs_any_uri_join() {
+        // RFC 3986 section 5.4.
+        let base = "http://a/b/c/d;p?q".parse::<XsAnyUri>().unwrap();
+        for (reference, expected) in [
+            ("g:h", "g:h"),
+            ("g", "http://a/b/c/g"),
+            ("./g", "http://a/b/c/g"),
+            ("g/", "http://a/b/c/g/"),
+            ("/g", "http://a/g"),
+            ("//g", "http://g"),
+            ("?y", "http://a/b/c/d;p?y"),
+            ("g?y", "http://a/b/c/g?y"),
+            ("#s", "http://a/b/c/d;p?q#s"),
+            ("", "http://a/b/c/d;p?q"),
+            (".", "http://a/b/c/"),
+            ("..", "http://a/b/"),
+            ("../g", "http://a/b/g"),
+            ("../../../g", "http://a/g"),
+            ("/./g", "http://a/g"),
+            ("g/../h", "http://a/b/c/h"),
+        ] {
+            let reference = reference.parse::<XsAnyUri>().unwrap();
+            assert_eq!(base.join(&reference).as_str(), expected);
+        }
+
+        let base = "video/".parse::<XsAnyUri>().unwrap();
+        let reference = "seg-1.mp4".parse::<XsAnyUri>().unwrap();
+        assert!(!base.is_absolute());
+        assert_eq!(base.join(&reference).as_str(), "video/seg-1.mp4");
+    }
+
+    #[test]
+    fn test_types_xs_any_uri_query_pairs() {
+        let uri = "http://a/b?x=1&y&z=a=b".parse::<XsAnyUri>().unwrap();
+
+        assert!(uri.is_absolute());
+        assert_eq!(
+            uri.query_pairs().collect::<Vec<_>>(),
+            vec![("x", "1"), ("y", ""), ("z", "a=b")]
+        );
+    }
+
+    #[cfg(all(feature = "parse", feature = "generate"))]
     #[test]
     fn test_types_url_type_serde() {
         let xml = r#"<URLType sourceURL="http://example.com/video.mp4" range="100-200"/>"#;
 
-        let ret = quick_xml::de::from_str::<Url>(&xml).unwrap();
+        let ret = quick_xml::de::from_str::<Url>(xml).unwrap();
 
         assert_eq!(
             ret,
@@ -346,14 +1545,125 @@ mod tests {
         assert_eq!(xml, se.as_str());
     }
 
+    #[cfg(feature = "generate")]
+    #[test]
+    fn test_types_url_type_ser_without_range() {
+        let url = Url::try_from(("http://example.com/init.mp4", (None, None))).unwrap();
+
+        let mut se = String::new();
+        let ser = quick_xml::se::Serializer::new(&mut se);
+        url.serialize(ser).unwrap();
+
+        assert_eq!(se, r#"<URLType sourceURL="http://example.com/init.mp4"/>"#);
+    }
+
+    #[test]
+    fn test_types_tuple_conversions() {
+        let url = Url::try_from(("http://example.com/init.mp4", (Some(0), Some(999)))).unwrap();
+
+        assert_eq!(
+            url,
+            Url::from((
+                Some(XsAnyUri("http://example.com/init.mp4".to_string())),
+                Some(SingleRFC7233RangeType::from((Some(0), Some(999))))
+            ))
+        );
+        assert!(Url::try_from(("a%zz", (None, None))).is_err());
+        assert_eq!(
+            Url::try_from(("http://example.com/init.mp4", (None, None)))
+                .unwrap()
+                .range,
+            None
+        );
+
+        let failover_content = FailoverContent::from((Some(true), [(0, Some(10)), (20, None)]));
+
+        assert_eq!(
+            failover_content.fcs_list,
+            vec![
+                Fcs {
+                    start_time: 0,
+                    duration: Some(10)
+                },
+                Fcs::from((20, None))
+            ]
+        );
+    }
+
+    #[test]
+    fn test_types_failover_content_builder() {
+        let failover_content = FailoverContentBuilder::default()
+            .valid(true)
+            .fcs((1625152800, Some(3600)))
+            .fcs_list([(1625156400, None)])
+            .build()
+            .unwrap();
+
+        assert_eq!(
+            failover_content,
+            FailoverContent::from((Some(true), [(1625152800, Some(3600)), (1625156400, None)]))
+        );
+
+        let failover_content = FailoverContentBuilder::default()
+            .valid(false)
+            .maybe_valid(None::<bool>)
+            .build()
+            .unwrap();
+
+        assert_eq!(failover_content.valid, None);
+    }
+
+    #[cfg(feature = "generate")]
+    #[test]
+    fn test_types_failover_content_ser_without_valid() {
+        let failover_content = FailoverContentBuilder::default()
+            .fcs((0, Some(10)))
+            .build()
+            .unwrap();
+
+        let mut se = String::new();
+        let ser = quick_xml::se::Serializer::new(&mut se);
+        failover_content.serialize(ser).unwrap();
+
+        assert_eq!(
+            se,
+            r#"<FailoverContent><FCS t="0" d="10"/></FailoverContent>"#
+        );
+    }
+
+    #[test]
+    fn test_types_failover_content_intervals() {
+        let failover_content =
+            FailoverContent::from((None, [(100, Some(50)), (120, Some(60)), (300, None)]));
+
+        assert!(!failover_content.covers(99));
+        assert!(failover_content.covers(100));
+        assert!(failover_content.covers(179));
+        assert!(!failover_content.covers(180));
+        assert!(failover_content.covers(u64::MAX - 1));
+        assert_eq!(
+            failover_content.gaps_between(0, 400),
+            vec![0..100, 180..300]
+        );
+        assert_eq!(failover_content.gaps_between(110, 170), vec![]);
+
+        let failover_content = FailoverContent::from((Some(false), [(100, Some(50))]));
+
+        assert!(failover_content.covers(99));
+        assert!(!failover_content.covers(100));
+        assert_eq!(failover_content.gaps_between(0, 120), vec![100..120]);
+        assert_eq!(failover_content.gaps_between(120, 120), vec![]);
+    }
+
+    #[cfg(all(feature = "parse", feature = "generate"))]
     #[test]
     fn test_types_failover_content_type_serde() {
-        let xml = r#"<FailoverContentType valid="true">
+        let xml = r#"<FailoverContent valid="true">
   <FCS t="1625152800" d="3600"/>
   <FCS t="1625156400"/>
-</FailoverContentType>"#;
+</FailoverContent>"#;
 
-        let ret = quick_xml::de::from_str::<FailoverContent>(&xml).unwrap();
+        let ret = quick_xml::de::from_str::<FailoverContent>(xml).unwrap();
 
         assert_eq!(
             ret,